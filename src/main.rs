@@ -43,6 +43,17 @@ fn print_help() {
     config.print();
 }
 
+/// Path of an extra pass saved next to the render, `scene.ppm` gives
+/// `scene_<pass>.ppm`
+fn pass_file(save_file: &str, pass: &str) -> String {
+    match save_file.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() && !extension.contains('/') => {
+            format!("{}_{}.{}", stem, pass, extension)
+        }
+        _ => format!("{}_{}", save_file, pass),
+    }
+}
+
 fn main() -> std::io::Result<()> {
     let args: Vec<String> = env::args().collect();
     let config = config::Config::from_args(&args);
@@ -60,11 +71,20 @@ fn main() -> std::io::Result<()> {
     if config.graphic {
         nannou_interface::run_nannou_interface();
     } else {
+        let renderer = renderer.unwrap();
+
         ppm_interface::PPMInterface::new(&config.save_file).write(
             config.width,
             config.height,
-            renderer.unwrap().render(&config),
-        )
+            renderer.render(&config),
+        );
+        if renderer.camera.ambient_occlusion_pass {
+            ppm_interface::PPMInterface::new(&pass_file(&config.save_file, "ao")).write(
+                config.width,
+                config.height,
+                renderer.render_ambient_occlusion(),
+            );
+        }
     }
 
     Ok(())
@@ -0,0 +1,83 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// ambient occlusion
+//
+
+use super::brdf;
+use super::primitives::Intersection;
+use super::Renderer;
+use crate::vector::Vector;
+use std::thread;
+
+impl Renderer {
+    /// Share of the hemisphere above a hit that no object hides closer than
+    /// the ambient occlusion distance, weighted by the cosine to the normal
+    pub(super) fn ambient_occlusion(&self, intersect: &Intersection, ray: Vector) -> f64 {
+        let mut normal = intersect.normal.normalize();
+        if normal.dot_product(ray) > 0.0 {
+            normal = normal * -1.0;
+        }
+        let origin = intersect.intersection_point + normal * self.camera.shadow_bias;
+        let samples = self.camera.ambient_occlusion_samples.max(1);
+        let open = (0..samples)
+            .filter(|_| {
+                !self.occluded(
+                    origin,
+                    brdf::sample_cosine(normal),
+                    self.camera.ambient_occlusion_distance,
+                )
+            })
+            .count();
+
+        open as f64 / samples as f64
+    }
+
+    /// Ambient occlusion of the surface seen through the middle of a pixel,
+    /// lights and the sky are left open
+    fn pixel_occlusion(&self, x: i64, y: i64) -> f64 {
+        let ray = self.camera.get_pixel_vectors(x, y, 1)[0];
+
+        match self.found_nearest_intersection(self.camera.transform.pos, ray) {
+            Some(intersect) if intersect.object.is_some() => {
+                self.ambient_occlusion(&intersect, ray)
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// Ambient occlusion of the whole image as gray pixels, white where
+    /// nothing hides the surface, to be saved as a pass of its own
+    pub fn render_ambient_occlusion(&self) -> Vec<u8> {
+        let (width, height) = (self.camera.lens.width, self.camera.lens.height);
+        let threads = self.camera.threads.max(1) as usize;
+        let mut pixels: Vec<u8> = vec![0; (width * height * 3) as usize];
+
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads as i64)
+                .map(|first| {
+                    scope.spawn(move || {
+                        (first..height)
+                            .step_by(threads)
+                            .map(|y| {
+                                let line: Vec<u8> = (0..width)
+                                    .flat_map(|x| [(self.pixel_occlusion(x, y) * 255.0) as u8; 3])
+                                    .collect();
+                                (y, line)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            for worker in workers {
+                for (y, line) in worker.join().unwrap() {
+                    let start = (y * width * 3) as usize;
+                    pixels[start..start + line.len()].copy_from_slice(&line);
+                }
+            }
+        });
+        pixels
+    }
+}
@@ -0,0 +1,188 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// microfacet brdf
+//
+
+use super::types::Texture;
+use crate::vector::Vector;
+use rand::Rng;
+use std::f64::consts::PI;
+
+/// Reflectance at normal incidence of dielectrics
+const DIELECTRIC_REFLECTANCE: f64 = 0.04;
+
+/// Perceptual roughness is squared, kept above zero so that mirrors stay
+/// numerically stable
+pub fn alpha(roughness: f64) -> f64 {
+    (roughness * roughness).max(1e-3)
+}
+
+/// Two unit vectors completing the normal into a right handed basis
+pub fn orthonormal_basis(normal: Vector) -> (Vector, Vector) {
+    let tangent = normal
+        .cross_product(if normal.x.abs() < 0.9 {
+            Vector {
+                x: 1.0,
+                y: 0.0,
+                z: 0.0,
+            }
+        } else {
+            Vector {
+                x: 0.0,
+                y: 1.0,
+                z: 0.0,
+            }
+        })
+        .normalize();
+    (tangent, normal.cross_product(tangent))
+}
+
+/// Reflectance at normal incidence, metals tint their reflections
+pub fn base_reflectance(albedo: Vector, metalness: f64) -> Vector {
+    Vector {
+        x: DIELECTRIC_REFLECTANCE,
+        y: DIELECTRIC_REFLECTANCE,
+        z: DIELECTRIC_REFLECTANCE,
+    } * (1.0 - metalness)
+        + albedo * metalness
+}
+
+/// Schlick approximation of the Fresnel term
+pub fn fresnel_schlick(f0: Vector, cos_theta: f64) -> Vector {
+    f0 + (f0 * -1.0 + 1.0) * (1.0 - cos_theta.clamp(0.0, 1.0)).powi(5)
+}
+
+/// Share of light reflected by a smooth boundary between two dielectrics,
+/// averaged over both polarizations, one under total internal reflection
+pub fn dielectric_fresnel(cos_i: f64, ior1: f64, ior2: f64) -> f64 {
+    let ratio = ior1 / ior2;
+    let sin_t2 = ratio * ratio * (1.0 - cos_i * cos_i);
+    if sin_t2 >= 1.0 {
+        return 1.0;
+    }
+    let cos_t = (1.0 - sin_t2).sqrt();
+    let perpendicular = (ior1 * cos_i - ior2 * cos_t) / (ior1 * cos_i + ior2 * cos_t);
+    let parallel = (ior1 * cos_t - ior2 * cos_i) / (ior1 * cos_t + ior2 * cos_i);
+
+    (perpendicular * perpendicular + parallel * parallel) / 2.0
+}
+
+/// GGX / Trowbridge-Reitz distribution of the microfacet normals
+pub fn ggx_distribution(n_dot_h: f64, alpha: f64) -> f64 {
+    let alpha2 = alpha * alpha;
+    let denominator = n_dot_h * n_dot_h * (alpha2 - 1.0) + 1.0;
+
+    alpha2 / (PI * denominator * denominator)
+}
+
+/// Smith masking and shadowing of the GGX microfacets
+pub fn smith_masking(n_dot_v: f64, n_dot_l: f64, alpha: f64) -> f64 {
+    let alpha2 = alpha * alpha;
+    let g1 = |cos: f64| 2.0 * cos / (cos + (alpha2 + (1.0 - alpha2) * cos * cos).sqrt());
+
+    g1(n_dot_v) * g1(n_dot_l)
+}
+
+/// Light reflected toward the viewer for a unit light coming from
+/// `light`, cosine included. The result is scaled by PI so that a white
+/// lambertian surface lit head-on returns one, like the Phong diffuse term
+pub fn cook_torrance(
+    albedo: Vector,
+    texture: &Texture,
+    normal: Vector,
+    view: Vector,
+    light: Vector,
+) -> Vector {
+    let n_dot_l = normal.dot_product(light);
+    let n_dot_v = normal.dot_product(view);
+    if n_dot_l <= 0.0 || n_dot_v <= 0.0 {
+        return Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+    }
+    let half = (light + view).normalize();
+    let alpha = alpha(texture.roughness);
+    let fresnel = fresnel_schlick(
+        base_reflectance(albedo, texture.metalness),
+        view.dot_product(half),
+    );
+    let specular = fresnel
+        * (ggx_distribution(normal.dot_product(half).max(0.0), alpha)
+            * smith_masking(n_dot_v, n_dot_l, alpha)
+            / (4.0 * n_dot_l * n_dot_v));
+    let diffuse = (fresnel * -1.0 + 1.0) * albedo * (1.0 - texture.metalness);
+
+    (diffuse + specular * PI) * n_dot_l
+}
+
+/// Microfacet normal drawn proportionally to the GGX distribution
+pub fn sample_ggx(normal: Vector, alpha: f64) -> Vector {
+    let mut rng = rand::thread_rng();
+    let random_a: f64 = rng.gen_range(0.0..1.0);
+    let random_b: f64 = rng.gen_range(0.0..std::f64::consts::TAU);
+    let cos_theta = ((1.0 - random_a) / (1.0 + (alpha * alpha - 1.0) * random_a)).sqrt();
+    let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+    let (tangent, bitangent) = orthonormal_basis(normal);
+
+    (tangent * (sin_theta * random_b.cos())
+        + bitangent * (sin_theta * random_b.sin())
+        + normal * cos_theta)
+        .normalize()
+}
+
+/// Direction drawn around the normal with a density following the cosine
+/// of its angle to it
+pub fn sample_cosine(normal: Vector) -> Vector {
+    let mut rng = rand::thread_rng();
+    let radius = rng.gen_range(0.0_f64..1.0).sqrt();
+    let angle: f64 = rng.gen_range(0.0..std::f64::consts::TAU);
+    let (tangent, bitangent) = orthonormal_basis(normal);
+
+    (tangent * (radius * angle.cos())
+        + bitangent * (radius * angle.sin())
+        + normal * (1.0 - radius * radius).max(0.0).sqrt())
+    .normalize()
+}
+
+/// Density of `sample_ggx` once the microfacet normal is turned into the
+/// reflection of `view`
+pub fn ggx_reflection_pdf(normal: Vector, view: Vector, reflected: Vector, alpha: f64) -> f64 {
+    let half = (view + reflected).normalize();
+    let n_dot_h = normal.dot_product(half);
+    let v_dot_h = view.dot_product(half);
+    if n_dot_h <= 0.0 || v_dot_h <= 0.0 {
+        return 0.0;
+    }
+
+    ggx_distribution(n_dot_h, alpha) * n_dot_h / (4.0 * v_dot_h)
+}
+
+/// Weight of a reflection ray built from a microfacet normal drawn with
+/// `sample_ggx`, the distribution cancels out with the sampling density
+pub fn reflection_weight(
+    f0: Vector,
+    alpha: f64,
+    normal: Vector,
+    view: Vector,
+    reflected: Vector,
+    half: Vector,
+) -> Vector {
+    let n_dot_l = normal.dot_product(reflected);
+    let n_dot_v = normal.dot_product(view);
+    let n_dot_h = normal.dot_product(half);
+    let v_dot_h = view.dot_product(half);
+    if n_dot_l <= 0.0 || n_dot_v <= 0.0 || n_dot_h <= 0.0 {
+        return Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+    }
+
+    fresnel_schlick(f0, v_dot_h)
+        * (smith_masking(n_dot_v, n_dot_l, alpha) * v_dot_h / (n_dot_h * n_dot_v))
+}
@@ -0,0 +1,312 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// bounding volume hierarchy
+//
+
+use super::primitives::Intersection;
+use super::types::BoundingBox;
+use crate::vector::Vector;
+
+const SAH_BINS: usize = 12;
+const LEAF_SIZE: usize = 4;
+const TRAVERSAL_COST: f64 = 1.0;
+const INTERSECTION_COST: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy)]
+struct BvhNode {
+    bounds: BoundingBox,
+    start: usize,
+    count: usize,
+    left: usize,
+    right: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    indices: Vec<usize>,
+    unbounded: Vec<usize>,
+}
+
+impl Bvh {
+    /// Builds the tree from one bounding box per item, items without
+    /// finite bounds (planes) are kept aside and tested for every ray
+    pub fn new(boxes: &[BoundingBox]) -> Bvh {
+        let mut bvh = Bvh::default();
+        let mut centers: Vec<Vector> = Vec::new();
+
+        for (index, bounds) in boxes.iter().enumerate() {
+            if bounds.is_finite() {
+                bvh.indices.push(index);
+            } else if !bounds.is_empty() {
+                bvh.unbounded.push(index);
+            }
+            centers.push(bounds.center());
+        }
+        if !bvh.indices.is_empty() {
+            let len = bvh.indices.len();
+            bvh.build(boxes, &centers, 0, len);
+        }
+        bvh
+    }
+
+    fn build(
+        &mut self,
+        boxes: &[BoundingBox],
+        centers: &[Vector],
+        start: usize,
+        end: usize,
+    ) -> usize {
+        let mut bounds = BoundingBox::default();
+        let mut centers_bounds = BoundingBox::default();
+
+        for &index in &self.indices[start..end] {
+            bounds = bounds.union(boxes[index]);
+            centers_bounds.grow(centers[index]);
+        }
+        let node_id = self.nodes.len();
+        self.nodes.push(BvhNode {
+            bounds,
+            start,
+            count: end - start,
+            left: 0,
+            right: 0,
+        });
+        if end - start <= LEAF_SIZE {
+            return node_id;
+        }
+        if let Some((axis, split)) =
+            self.find_split(boxes, centers, start, end, bounds, centers_bounds)
+        {
+            let mid = self.partition(centers, start, end, axis, split);
+            if mid != start && mid != end {
+                let left = self.build(boxes, centers, start, mid);
+                let right = self.build(boxes, centers, mid, end);
+                self.nodes[node_id].count = 0;
+                self.nodes[node_id].left = left;
+                self.nodes[node_id].right = right;
+            }
+        }
+        node_id
+    }
+
+    fn axis_value(vector: Vector, axis: usize) -> f64 {
+        match axis {
+            0 => vector.x,
+            1 => vector.y,
+            _ => vector.z,
+        }
+    }
+
+    fn find_split(
+        &self,
+        boxes: &[BoundingBox],
+        centers: &[Vector],
+        start: usize,
+        end: usize,
+        bounds: BoundingBox,
+        centers_bounds: BoundingBox,
+    ) -> Option<(usize, f64)> {
+        let parent_area = bounds.surface_area();
+        let mut best_cost = INTERSECTION_COST * (end - start) as f64;
+        let mut best_split: Option<(usize, f64)> = None;
+
+        for axis in 0..3 {
+            let axis_min = Bvh::axis_value(centers_bounds.min, axis);
+            let axis_max = Bvh::axis_value(centers_bounds.max, axis);
+            if axis_max - axis_min <= f64::EPSILON {
+                continue;
+            }
+            let mut bins = [(BoundingBox::default(), 0_usize); SAH_BINS];
+            let scale = SAH_BINS as f64 / (axis_max - axis_min);
+
+            for &index in &self.indices[start..end] {
+                let bin = (((Bvh::axis_value(centers[index], axis) - axis_min) * scale) as usize)
+                    .min(SAH_BINS - 1);
+                bins[bin].0 = bins[bin].0.union(boxes[index]);
+                bins[bin].1 += 1;
+            }
+            for split in 1..SAH_BINS {
+                let (mut left_bounds, mut left_count) = (BoundingBox::default(), 0);
+                let (mut right_bounds, mut right_count) = (BoundingBox::default(), 0);
+                for bin in &bins[..split] {
+                    left_bounds = left_bounds.union(bin.0);
+                    left_count += bin.1;
+                }
+                for bin in &bins[split..] {
+                    right_bounds = right_bounds.union(bin.0);
+                    right_count += bin.1;
+                }
+                if left_count == 0 || right_count == 0 {
+                    continue;
+                }
+                let cost = TRAVERSAL_COST
+                    + INTERSECTION_COST
+                        * (left_bounds.surface_area() * left_count as f64
+                            + right_bounds.surface_area() * right_count as f64)
+                        / parent_area;
+                if cost < best_cost {
+                    best_cost = cost;
+                    best_split = Some((axis, axis_min + split as f64 / scale));
+                }
+            }
+        }
+        best_split
+    }
+
+    fn partition(
+        &mut self,
+        centers: &[Vector],
+        start: usize,
+        end: usize,
+        axis: usize,
+        split: f64,
+    ) -> usize {
+        let mut mid = start;
+
+        for i in start..end {
+            if Bvh::axis_value(centers[self.indices[i]], axis) < split {
+                self.indices.swap(i, mid);
+                mid += 1;
+            }
+        }
+        mid
+    }
+
+    /// Returns the nearest intersection found by `intersect` among the
+    /// items whose bounding box is crossed by the ray
+    pub fn closest_intersection<'a, F>(
+        &self,
+        ray: Vector,
+        origin: Vector,
+        mut intersect: F,
+    ) -> Option<Intersection<'a>>
+    where
+        F: FnMut(usize) -> Option<Intersection<'a>>,
+    {
+        let mut found_intersection: Option<Intersection> = None;
+        let mut smallest_distance: f64 = f64::INFINITY;
+        let ray_len = ray.len();
+
+        let mut test = |index: usize, smallest_distance: &mut f64| {
+            if let Some(inters) = intersect(index) {
+                let distance_found = (inters.intersection_point - origin).len();
+                if distance_found < *smallest_distance {
+                    *smallest_distance = distance_found;
+                    found_intersection = Some(inters);
+                }
+            }
+        };
+
+        for &index in &self.unbounded {
+            test(index, &mut smallest_distance);
+        }
+        if self.nodes.is_empty() {
+            return found_intersection;
+        }
+        let mut stack: Vec<usize> = vec![0];
+        while let Some(node_id) = stack.pop() {
+            let node = self.nodes[node_id];
+            match node.bounds.intersection(ray, origin) {
+                Some(progress) if progress * ray_len <= smallest_distance => {}
+                _ => continue,
+            }
+            if node.count > 0 {
+                for i in node.start..node.start + node.count {
+                    test(self.indices[i], &mut smallest_distance);
+                }
+                continue;
+            }
+            let left = self.nodes[node.left].bounds.intersection(ray, origin);
+            let right = self.nodes[node.right].bounds.intersection(ray, origin);
+            // push the farthest child first so the nearest is visited first
+            match (left, right) {
+                (Some(l), Some(r)) if l < r => {
+                    stack.push(node.right);
+                    stack.push(node.left);
+                }
+                (Some(_), Some(_)) => {
+                    stack.push(node.left);
+                    stack.push(node.right);
+                }
+                (Some(_), None) => stack.push(node.left),
+                (None, Some(_)) => stack.push(node.right),
+                (None, None) => {}
+            }
+        }
+        found_intersection
+    }
+
+    /// Calls `visit` for every item whose bounding box, grown by `margin`,
+    /// holds the point
+    pub fn point_query<F>(&self, point: Vector, margin: f64, mut visit: F)
+    where
+        F: FnMut(usize),
+    {
+        for &index in &self.unbounded {
+            visit(index);
+        }
+        if self.nodes.is_empty() {
+            return;
+        }
+        let mut stack: Vec<usize> = vec![0];
+        while let Some(node_id) = stack.pop() {
+            let node = self.nodes[node_id];
+            if !node.bounds.contains(point, margin) {
+                continue;
+            }
+            if node.count > 0 {
+                for i in node.start..node.start + node.count {
+                    visit(self.indices[i]);
+                }
+            } else {
+                stack.push(node.left);
+                stack.push(node.right);
+            }
+        }
+    }
+
+    /// Returns true as soon as `occludes` accepts an item crossed by the ray
+    /// closer than `max_distance`, used for shadow rays
+    pub fn any_intersection<F>(
+        &self,
+        ray: Vector,
+        origin: Vector,
+        max_distance: f64,
+        mut occludes: F,
+    ) -> bool
+    where
+        F: FnMut(usize) -> bool,
+    {
+        for &index in &self.unbounded {
+            if occludes(index) {
+                return true;
+            }
+        }
+        if self.nodes.is_empty() {
+            return false;
+        }
+        let ray_len = ray.len();
+        let mut stack: Vec<usize> = vec![0];
+        while let Some(node_id) = stack.pop() {
+            let node = self.nodes[node_id];
+            match node.bounds.intersection(ray, origin) {
+                Some(progress) if progress * ray_len <= max_distance => {}
+                _ => continue,
+            }
+            if node.count > 0 {
+                for i in node.start..node.start + node.count {
+                    if occludes(self.indices[i]) {
+                        return true;
+                    }
+                }
+            } else {
+                stack.push(node.left);
+                stack.push(node.right);
+            }
+        }
+        false
+    }
+}
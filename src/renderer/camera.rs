@@ -35,6 +35,15 @@ impl Lens {
     }
 }
 
+/// How the light reaching the camera is gathered
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Integrator {
+    /// Direct lighting with mirror and glossy reflections, the stylized look
+    Whitted,
+    /// Unbiased path tracing with next event estimation
+    Path,
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 pub struct Camera {
     pub transform: Transform,
@@ -58,6 +67,19 @@ pub struct Camera {
     pub display_normals: bool,
     pub display_location: bool,
     pub display_dot_product: bool,
+    pub display_ambient_occlusion: bool,
+    /// Dims the ambient light by how much of the surroundings hide a surface
+    pub ambient_occlusion: bool,
+    pub ambient_occlusion_samples: u64,
+    /// Distance past which an object no longer hides a surface
+    pub ambient_occlusion_distance: f64,
+    /// Saves the ambient occlusion next to the render as its own image
+    pub ambient_occlusion_pass: bool,
+    pub integrator: Integrator,
+    /// Photons shot toward specular objects for caustics, none disables them
+    pub caustic_photons: u64,
+    pub caustic_radius: f64,
+    pub caustic_neighbours: u64,
 }
 
 impl Camera {
@@ -84,6 +106,15 @@ impl Camera {
             display_normals: false,
             display_location: false,
             display_dot_product: false,
+            display_ambient_occlusion: false,
+            ambient_occlusion: false,
+            ambient_occlusion_samples: 16,
+            ambient_occlusion_distance: 1.0,
+            ambient_occlusion_pass: false,
+            integrator: Integrator::Whitted,
+            caustic_photons: 0,
+            caustic_radius: 0.1,
+            caustic_neighbours: 64,
         };
         camera.calculate_lens_distance();
         camera.calculate_lens_size();
@@ -0,0 +1,208 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// caustics
+//
+
+use super::brdf;
+use super::photon_map::{Photon, PhotonMap};
+use super::primitives::Intersection;
+use super::types::{Shading, Texture};
+use super::{Medium, Renderer};
+use crate::vector::Vector;
+use rand::Rng;
+use std::f64::consts::PI;
+
+/// Roughness under which a reflective material still focuses light
+const SPECULAR_ROUGHNESS: f64 = 0.2;
+
+/// Whether a material bends or mirrors light sharply enough to focus it
+/// into caustics worth shooting photons at
+fn is_specular(texture: &Texture) -> bool {
+    texture.transmission > 0.0
+        || (texture.metalness > 0.0 && texture.roughness < SPECULAR_ROUGHNESS)
+}
+
+impl Renderer {
+    /// Shoots photons from the point, directional and spot lights through
+    /// the disk every specular object covers, those landing on another
+    /// surface after bending or bouncing off one of them are kept
+    pub fn build_photon_map(&mut self) {
+        self.photon_map = None;
+        if self.camera.caustic_photons == 0 {
+            return;
+        }
+        let targets: Vec<(Vector, f64)> = self
+            .primitives
+            .iter()
+            .filter(|object| {
+                is_specular(&object.get_texture()) && object.bounding_box().is_finite()
+            })
+            .map(|object| {
+                let bounds = object.bounding_box();
+                (
+                    (bounds.min + bounds.max) / 2.0,
+                    (bounds.max - bounds.min).len() / 2.0,
+                )
+            })
+            .collect();
+        if targets.is_empty() {
+            return;
+        }
+        let scene = self.get_scene_bounds();
+        let reach = (scene.max - scene.min).len();
+        let per_target = (self.camera.caustic_photons as usize
+            / (targets.len() * self.lights.lights.len().max(1)))
+        .max(1);
+        let mut rng = rand::thread_rng();
+        let mut photons = Vec::new();
+
+        for light in self.lights.lights.iter() {
+            for (center, radius) in targets.iter() {
+                // only lights reduced to a point or a direction shoot photons
+                let axis = match light.sample(*center, (0.5, 0.5), self.camera, &self.primitives) {
+                    Some(sample) if sample.pdf == 0.0 => sample.direction,
+                    _ => continue,
+                };
+                let (tangent, bitangent) = brdf::orthonormal_basis(axis);
+                for _ in 0..per_target {
+                    let distance = radius * rng.gen_range(0.0_f64..1.0).sqrt();
+                    let angle: f64 = rng.gen_range(0.0..std::f64::consts::TAU);
+                    let target =
+                        *center + (tangent * angle.cos() + bitangent * angle.sin()) * distance;
+                    let random = (rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0));
+                    let sample = match light.sample(target, random, self.camera, &self.primitives) {
+                        Some(sample) => sample,
+                        None => continue,
+                    };
+                    let start = if sample.distance.is_finite() {
+                        sample.distance
+                    } else {
+                        reach + radius
+                    };
+                    let power = sample.radiance
+                        * (sample.direction.dot_product(axis).max(0.0) * PI * radius * radius
+                            / per_target as f64);
+                    self.trace_photon(
+                        target + sample.direction * start,
+                        sample.direction * -1.0,
+                        power,
+                        &mut photons,
+                    );
+                }
+            }
+        }
+        let photon_map = PhotonMap::new(photons);
+        // nothing to gather when no photon got focused
+        if !photon_map.is_empty() {
+            self.photon_map = Some(photon_map);
+        }
+    }
+
+    /// Share of the light a specular surface reflects back
+    fn specular_reflectance(
+        &self,
+        intersect: &Intersection,
+        normal: Vector,
+        ray: Vector,
+    ) -> Vector {
+        let object = intersect.object.unwrap();
+        let texture = object.get_texture();
+        let texture_coordinates =
+            object.surface_position(intersect.intersection_point - object.get_transform().pos);
+        let albedo = texture
+            .texture(texture_coordinates.x, texture_coordinates.y)
+            .as_vector();
+
+        if texture.shading == Shading::Pbr {
+            brdf::fresnel_schlick(
+                brdf::base_reflectance(albedo, texture.metalness),
+                -normal.dot_product(ray),
+            )
+        } else {
+            albedo * texture.metalness + texture.specular * (1.0 - texture.metalness)
+        }
+    }
+
+    /// Follows a photon through specular surfaces, it is stored on every
+    /// opaque surface it meets once it went through at least one of them
+    fn trace_photon(&self, origin: Vector, ray: Vector, power: Vector, photons: &mut Vec<Photon>) {
+        let mut rng = rand::thread_rng();
+        let (mut origin, mut ray, mut power) = (origin, ray.normalize(), power);
+        let mut media: Vec<Medium> = Vec::new();
+        let mut focused = false;
+
+        for _ in 0..=self.camera.recursivity.max(1) {
+            let mut intersect = match self.found_nearest_intersection_fast(origin, ray) {
+                Some(intersect) => intersect,
+                None => return,
+            };
+            if let Some(medium) = media.last() {
+                power = power * medium.transmittance((intersect.intersection_point - origin).len());
+            }
+            self.apply_normal_map(&mut intersect);
+            let texture = intersect.object.unwrap().get_texture();
+            if rng.gen_range(0.0..1.0) < texture.transmission {
+                (origin, ray) = self.dielectric_bounce(&intersect, ray, &mut media);
+                focused = true;
+                continue;
+            }
+            if focused {
+                photons.push(Photon {
+                    position: intersect.intersection_point,
+                    direction: ray,
+                    power,
+                });
+            }
+            if texture.roughness >= SPECULAR_ROUGHNESS {
+                return;
+            }
+            let mut normal = intersect.normal.normalize();
+            if normal.dot_product(ray) > 0.0 {
+                normal = normal * -1.0;
+            }
+            // russian roulette keeps the power of the surviving photons steady
+            let reflectance = self.specular_reflectance(&intersect, normal, ray);
+            let survival = reflectance.x.max(reflectance.y).max(reflectance.z).min(1.0);
+            if survival <= 0.0 || rng.gen_range(0.0..1.0) >= survival {
+                return;
+            }
+            power = power * reflectance / survival;
+            focused = true;
+            origin = intersect.intersection_point + normal * self.camera.shadow_bias;
+            ray = ray - normal * 2.0 * ray.dot_product(normal);
+        }
+    }
+
+    /// Light focused on a surface by specular objects, estimated from the
+    /// density of the photons around the hit
+    pub(super) fn caustic_light(&self, intersect: &Intersection, ray: Vector) -> Vector {
+        let mut light = Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let photon_map = match &self.photon_map {
+            Some(photon_map) => photon_map,
+            None => return light,
+        };
+        let (photons, radius2) = photon_map.nearest(
+            intersect.intersection_point,
+            self.camera.caustic_neighbours as usize,
+            self.camera.caustic_radius,
+        );
+        let normal = intersect.normal.normalize();
+
+        for photon in photons {
+            let incoming = (photon.direction * -1.0).normalize();
+            let cos_theta = incoming.dot_product(normal);
+            if cos_theta <= 0.0 {
+                continue;
+            }
+            // the density already spreads the power over the surface
+            light = light + self.scattering(intersect, ray, incoming) * photon.power / cos_theta;
+        }
+        light / (PI * radius2)
+    }
+}
@@ -0,0 +1,331 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// environment
+//
+
+use crate::vector::Vector;
+use std::f64::consts::PI;
+use std::fs;
+
+/// Equirectangular high dynamic range map surrounding the scene, the top
+/// row is straight up and the middle column straight ahead of an unrotated
+/// camera
+pub struct Environment {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[f32; 3]>,
+    /// Turn around the vertical axis in degrees
+    pub rotation: f64,
+    pub intensity: f64,
+    /// Directions drawn for each shaded point when lighting the scene
+    pub samples: usize,
+    /// Share of the luminance held by each pixel, corrected for the
+    /// stretching of the poles
+    weights: Vec<f64>,
+    total: f64,
+    /// Cumulative distributions of the rows and of the pixels of each row
+    rows: Vec<f64>,
+    columns: Vec<f64>,
+}
+
+fn luminance(pixel: [f32; 3]) -> f64 {
+    0.2126 * pixel[0] as f64 + 0.7152 * pixel[1] as f64 + 0.0722 * pixel[2] as f64
+}
+
+fn rgbe_to_rgb(rgbe: [u8; 4]) -> [f32; 3] {
+    if rgbe[3] == 0 {
+        return [0.0, 0.0, 0.0];
+    }
+    let factor = 2.0_f32.powi(rgbe[3] as i32 - (128 + 8));
+
+    [
+        (rgbe[0] as f32 + 0.5) * factor,
+        (rgbe[1] as f32 + 0.5) * factor,
+        (rgbe[2] as f32 + 0.5) * factor,
+    ]
+}
+
+fn uv_to_direction(u: f64, v: f64, rotation: f64) -> Vector {
+    let phi = (u - 0.5) * 2.0 * PI + rotation.to_radians();
+    let theta = v * PI;
+
+    Vector {
+        x: theta.sin() * phi.sin(),
+        y: theta.sin() * phi.cos(),
+        z: theta.cos(),
+    }
+}
+
+/// Reads one scanline, run length encoded or stored flat
+fn read_scanline(data: &[u8], cursor: &mut usize, width: usize) -> Option<Vec<[u8; 4]>> {
+    let mut line = vec![[0u8; 4]; width];
+    let header = data.get(*cursor..*cursor + 4)?;
+
+    if !(8..32768).contains(&width)
+        || header[0] != 2
+        || header[1] != 2
+        || ((header[2] as usize) << 8 | header[3] as usize) != width
+    {
+        for pixel in line.iter_mut() {
+            pixel.copy_from_slice(data.get(*cursor..*cursor + 4)?);
+            *cursor += 4;
+        }
+        return Some(line);
+    }
+    *cursor += 4;
+    // each channel is encoded on its own, one after the other
+    for channel in 0..4 {
+        let mut column = 0;
+        while column < width {
+            let count = *data.get(*cursor)? as usize;
+            *cursor += 1;
+            if count > 128 {
+                let value = *data.get(*cursor)?;
+                *cursor += 1;
+                for pixel in line.get_mut(column..column + count - 128)? {
+                    pixel[channel] = value;
+                }
+                column += count - 128;
+            } else {
+                if count == 0 {
+                    return None;
+                }
+                let values = data.get(*cursor..*cursor + count)?;
+                for (pixel, value) in line.get_mut(column..column + count)?.iter_mut().zip(values) {
+                    pixel[channel] = *value;
+                }
+                *cursor += count;
+                column += count;
+            }
+        }
+    }
+    Some(line)
+}
+
+/// Decodes a Radiance RGBE picture into linear colors
+pub fn decode_hdr(data: &[u8]) -> Option<(usize, usize, Vec<[f32; 3]>)> {
+    let mut cursor = 0;
+    let next_line = |cursor: &mut usize| -> Option<String> {
+        let end = data
+            .get(*cursor..)?
+            .iter()
+            .position(|byte| *byte == b'\n')?;
+        let line = String::from_utf8_lossy(&data[*cursor..*cursor + end]).to_string();
+        *cursor += end + 1;
+        Some(line)
+    };
+
+    if !next_line(&mut cursor)?.starts_with("#?") {
+        return None;
+    }
+    loop {
+        let line = next_line(&mut cursor)?;
+        if line.trim().is_empty() {
+            break;
+        }
+        if line.starts_with("FORMAT=") && line.trim() != "FORMAT=32-bit_rle_rgbe" {
+            return None;
+        }
+    }
+    let resolution = next_line(&mut cursor)?;
+    let fields: Vec<&str> = resolution.split_ascii_whitespace().collect();
+    if fields.len() != 4 || fields[0] != "-Y" || fields[2] != "+X" {
+        return None;
+    }
+    let height: usize = fields[1].parse().ok()?;
+    let width: usize = fields[3].parse().ok()?;
+    let mut pixels = Vec::with_capacity(width * height);
+
+    for _ in 0..height {
+        let line = read_scanline(data, &mut cursor, width)?;
+        pixels.extend(line.into_iter().map(rgbe_to_rgb));
+    }
+    Some((width, height, pixels))
+}
+
+impl Environment {
+    pub fn from_file(file: &str, rotation: f64, intensity: f64) -> Option<Environment> {
+        let data = fs::read(file).ok()?;
+        let (width, height, pixels) = decode_hdr(&data)?;
+
+        Some(Environment::new(width, height, pixels, rotation, intensity))
+    }
+
+    /// Bakes the light coming from every direction into a map
+    pub fn from_radiance(
+        width: usize,
+        height: usize,
+        radiance: impl Fn(Vector) -> Vector,
+    ) -> Environment {
+        let mut pixels = Vec::with_capacity(width * height);
+
+        for row in 0..height {
+            for column in 0..width {
+                let color = radiance(uv_to_direction(
+                    (column as f64 + 0.5) / width as f64,
+                    (row as f64 + 0.5) / height as f64,
+                    0.0,
+                ));
+                pixels.push([color.x as f32, color.y as f32, color.z as f32]);
+            }
+        }
+        Environment::new(width, height, pixels, 0.0, 1.0)
+    }
+
+    pub fn new(
+        width: usize,
+        height: usize,
+        pixels: Vec<[f32; 3]>,
+        rotation: f64,
+        intensity: f64,
+    ) -> Environment {
+        let mut weights = Vec::with_capacity(width * height);
+        let mut rows = Vec::with_capacity(height);
+        let mut columns = Vec::with_capacity(width * height);
+        let mut total = 0.0;
+
+        for row in 0..height {
+            let sin_theta = ((row as f64 + 0.5) / height as f64 * PI).sin();
+            let start = columns.len();
+            let mut row_total = 0.0;
+            for column in 0..width {
+                let weight = luminance(pixels[row * width + column]) * sin_theta;
+                weights.push(weight);
+                row_total += weight;
+                columns.push(row_total);
+            }
+            for cumulated in columns[start..].iter_mut() {
+                *cumulated /= row_total.max(f64::MIN_POSITIVE);
+            }
+            total += row_total;
+            rows.push(total);
+        }
+        for cumulated in rows.iter_mut() {
+            *cumulated /= total.max(f64::MIN_POSITIVE);
+        }
+        Environment {
+            width,
+            height,
+            pixels,
+            rotation,
+            intensity,
+            samples: 16,
+            weights,
+            total,
+            rows,
+            columns,
+        }
+    }
+
+    fn direction_to_uv(&self, direction: Vector) -> (f64, f64) {
+        let direction = direction.normalize();
+
+        (
+            (0.5 + direction.x.atan2(direction.y) / (2.0 * PI) - self.rotation / 360.0)
+                .rem_euclid(1.0),
+            direction.z.clamp(-1.0, 1.0).acos() / PI,
+        )
+    }
+
+    fn uv_to_direction(&self, u: f64, v: f64) -> Vector {
+        uv_to_direction(u, v, self.rotation)
+    }
+
+    fn pixel(&self, column: i64, row: i64) -> Vector {
+        let column = column.rem_euclid(self.width as i64) as usize;
+        let row = row.clamp(0, self.height as i64 - 1) as usize;
+        let pixel = self.pixels[row * self.width + column];
+
+        Vector {
+            x: pixel[0] as f64,
+            y: pixel[1] as f64,
+            z: pixel[2] as f64,
+        }
+    }
+
+    /// Light coming from a direction, blended between the four closest
+    /// pixels
+    pub fn radiance(&self, direction: Vector) -> Vector {
+        if self.pixels.is_empty() {
+            return Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            };
+        }
+        let (u, v) = self.direction_to_uv(direction);
+        let x = u * self.width as f64 - 0.5;
+        let y = v * self.height as f64 - 0.5;
+        let (column, row) = (x.floor() as i64, y.floor() as i64);
+        let (tx, ty) = (x - x.floor(), y - y.floor());
+        let top = self.pixel(column, row) * (1.0 - tx) + self.pixel(column + 1, row) * tx;
+        let bottom =
+            self.pixel(column, row + 1) * (1.0 - tx) + self.pixel(column + 1, row + 1) * tx;
+
+        (top * (1.0 - ty) + bottom * ty) * self.intensity
+    }
+
+    /// Solid angle density of `sample` drawing a direction
+    pub fn pdf(&self, direction: Vector) -> f64 {
+        if self.total <= 0.0 {
+            return 0.0;
+        }
+        let (u, v) = self.direction_to_uv(direction);
+        let column = ((u * self.width as f64) as usize).min(self.width - 1);
+        let row = ((v * self.height as f64) as usize).min(self.height - 1);
+        let sin_theta = (v * PI).sin();
+        if sin_theta <= 0.0 {
+            return 0.0;
+        }
+
+        self.weights[row * self.width + column] / self.total * (self.width * self.height) as f64
+            / (2.0 * PI * PI * sin_theta)
+    }
+
+    /// Direction drawn proportionally to the brightness of the map, with
+    /// its density
+    pub fn sample(&self, random: (f64, f64)) -> Option<(Vector, f64)> {
+        if self.total <= 0.0 {
+            return None;
+        }
+        let row = self
+            .rows
+            .partition_point(|cumulated| *cumulated < random.0)
+            .min(self.height - 1);
+        let columns = &self.columns[row * self.width..(row + 1) * self.width];
+        let column = columns
+            .partition_point(|cumulated| *cumulated < random.1)
+            .min(self.width - 1);
+
+        // spread the sample inside of the pixel by reusing the random values
+        let below = |cumulated: &[f64], index: usize| {
+            if index == 0 {
+                0.0
+            } else {
+                cumulated[index - 1]
+            }
+        };
+        let row_jitter = ((random.0 - below(&self.rows, row))
+            / (self.rows[row] - below(&self.rows, row)).max(f64::MIN_POSITIVE))
+        .clamp(0.0, 1.0);
+        let column_jitter = ((random.1 - below(columns, column))
+            / (columns[column] - below(columns, column)).max(f64::MIN_POSITIVE))
+        .clamp(0.0, 1.0);
+        let u = (column as f64 + column_jitter) / self.width as f64;
+        let v = (row as f64 + row_jitter) / self.height as f64;
+        let direction = self.uv_to_direction(u, v);
+        let sin_theta = (v * PI).sin();
+        if sin_theta <= 0.0 {
+            return None;
+        }
+
+        Some((
+            direction,
+            self.weights[row * self.width + column] / self.total
+                * (self.width * self.height) as f64
+                / (2.0 * PI * PI * sin_theta),
+        ))
+    }
+}
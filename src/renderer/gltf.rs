@@ -0,0 +1,662 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// glTF 2.0 scene import
+//
+
+use super::bvh::Bvh;
+use super::lights::{Directional, Light, Point, Spot};
+use super::primitives::{mesh::Mesh, parent::Parent, Object};
+use super::types::{
+    Color, Image, ImageFilter, ImageWrap, Sampler, Shading, Texture, TexturesTypes, Transform,
+};
+use crate::matrix::Matrix;
+use crate::vector::Vector;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const GLB_MAGIC: u32 = 0x4654_6c67;
+const GLB_JSON_CHUNK: u32 = 0x4e4f_534a;
+const GLB_BIN_CHUNK: u32 = 0x004e_4942;
+const TRIANGLES_MODE: u64 = 4;
+
+pub struct GltfCamera {
+    pub transform: Transform,
+    /// Vertical field of view in radians
+    pub yfov: f64,
+}
+
+pub struct GltfScene {
+    pub primitives: Vec<Box<dyn Object + Send + Sync>>,
+    pub lights: Vec<Box<dyn Light + Send + Sync>>,
+    pub camera: Option<GltfCamera>,
+}
+
+pub fn is_gltf_file(file: &str) -> bool {
+    let extension = Path::new(file)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+
+    matches!(extension.as_deref(), Some("gltf") | Some("glb"))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut result: Vec<u8> = Vec::new();
+    let mut bits: u32 = 0;
+    let mut bit_count = 0;
+
+    for character in text.bytes() {
+        let value = match character {
+            b'A'..=b'Z' => character - b'A',
+            b'a'..=b'z' => character - b'a' + 26,
+            b'0'..=b'9' => character - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => break,
+            b'\r' | b'\n' | b' ' => continue,
+            _ => return None,
+        };
+        bits = (bits << 6) | value as u32;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            result.push((bits >> bit_count) as u8);
+        }
+    }
+    Some(result)
+}
+
+/// Data of a `data:` URI or of a file relative to the glTF
+fn load_uri(directory: &Path, uri: &str) -> Option<Vec<u8>> {
+    if let Some(data) = uri.strip_prefix("data:") {
+        let (_, encoded) = data.split_once(";base64,")?;
+        return decode_base64(encoded);
+    }
+    fs::read(directory.join(uri)).ok()
+}
+
+struct Document {
+    json: Value,
+    directory: PathBuf,
+    file: String,
+    buffers: Vec<Vec<u8>>,
+}
+
+impl Document {
+    fn open(file: &str) -> Option<Document> {
+        let data = fs::read(file).ok()?;
+        let mut binary: Option<Vec<u8>> = None;
+        let json: Value = if read_u32(&data, 0) == Some(GLB_MAGIC) {
+            let mut json: Option<Value> = None;
+            let mut offset = 12;
+            while let (Some(length), Some(kind)) =
+                (read_u32(&data, offset), read_u32(&data, offset + 4))
+            {
+                let chunk = data.get(offset + 8..offset + 8 + length as usize)?;
+                match kind {
+                    GLB_JSON_CHUNK => json = serde_json::from_slice(chunk).ok(),
+                    GLB_BIN_CHUNK => binary = Some(chunk.to_vec()),
+                    _ => {}
+                }
+                offset += 8 + length as usize;
+            }
+            json?
+        } else {
+            serde_json::from_slice(&data).ok()?
+        };
+        let directory = Path::new(file)
+            .parent()
+            .unwrap_or(Path::new(""))
+            .to_path_buf();
+        let mut buffers: Vec<Vec<u8>> = Vec::new();
+
+        for buffer in json["buffers"].as_array().unwrap_or(&Vec::new()) {
+            let data = match buffer["uri"].as_str() {
+                Some(uri) => load_uri(&directory, uri),
+                None => binary.take(),
+            };
+            match data {
+                Some(data) => buffers.push(data),
+                None => {
+                    println!("Invalid buffer in \"{}\" !", file);
+                    return None;
+                }
+            }
+        }
+        Some(Document {
+            json,
+            directory,
+            file: file.to_string(),
+            buffers,
+        })
+    }
+
+    fn buffer_view(&self, index: &Value) -> Option<(&[u8], usize)> {
+        let view = &self.json["bufferViews"][index.as_u64()? as usize];
+        let buffer = self.buffers.get(view["buffer"].as_u64()? as usize)?;
+        let start = view["byteOffset"].as_u64().unwrap_or(0) as usize;
+        let length = view["byteLength"].as_u64()? as usize;
+
+        Some((
+            buffer.get(start..start + length)?,
+            view["byteStride"].as_u64().unwrap_or(0) as usize,
+        ))
+    }
+
+    /// Every element of an accessor as floats, normalized integers are mapped to [0, 1]
+    fn accessor(&self, index: &Value) -> Option<Vec<Vec<f64>>> {
+        let accessor = &self.json["accessors"][index.as_u64()? as usize];
+        let count = accessor["count"].as_u64()? as usize;
+        let components = match accessor["type"].as_str()? {
+            "SCALAR" => 1,
+            "VEC2" => 2,
+            "VEC3" => 3,
+            "VEC4" => 4,
+            _ => return None,
+        };
+        let component_type = accessor["componentType"].as_u64()?;
+        let size = match component_type {
+            5120 | 5121 => 1,
+            5122 | 5123 => 2,
+            5125 | 5126 => 4,
+            _ => return None,
+        };
+        let normalized = accessor["normalized"].as_bool().unwrap_or(false);
+
+        if !accessor["bufferView"].is_u64() {
+            return Some(vec![vec![0.0; components]; count]);
+        }
+        let (data, stride) = self.buffer_view(&accessor["bufferView"])?;
+        let stride = if stride == 0 {
+            size * components
+        } else {
+            stride
+        };
+        let offset = accessor["byteOffset"].as_u64().unwrap_or(0) as usize;
+        let mut elements: Vec<Vec<f64>> = Vec::with_capacity(count);
+
+        for element in 0..count {
+            let mut values: Vec<f64> = Vec::with_capacity(components);
+            for component in 0..components {
+                let start = offset + element * stride + component * size;
+                let bytes = data.get(start..start + size)?;
+                let value = match component_type {
+                    5120 => bytes[0] as i8 as f64,
+                    5121 => bytes[0] as f64,
+                    5122 => i16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+                    5123 => u16::from_le_bytes([bytes[0], bytes[1]]) as f64,
+                    5125 => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+                    _ => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64,
+                };
+                values.push(match (normalized, component_type) {
+                    (true, 5120) => (value / 127.0).max(-1.0),
+                    (true, 5121) => value / 255.0,
+                    (true, 5122) => (value / 32767.0).max(-1.0),
+                    (true, 5123) => value / 65535.0,
+                    _ => value,
+                });
+            }
+            elements.push(values);
+        }
+        Some(elements)
+    }
+
+    /// Image of a texture, embedded images are decoded in memory and cached
+    /// under the glTF file and their index
+    fn image(&self, texture: &Value) -> Option<Image> {
+        let index = self.json["textures"][texture.as_u64()? as usize]["source"].as_u64()?;
+        let image = &self.json["images"][index as usize];
+
+        if let Some(uri) = image["uri"].as_str() {
+            if !uri.starts_with("data:") {
+                return Some(Image::from_file(
+                    &self.directory.join(uri).to_string_lossy(),
+                ));
+            }
+        }
+        let data = match image["uri"].as_str() {
+            Some(uri) => load_uri(&self.directory, uri)?,
+            None => self.buffer_view(&image["bufferView"])?.0.to_vec(),
+        };
+        Some(Image::from_bytes(
+            &format!("{}#image{}", self.file, index),
+            &data,
+        ))
+    }
+
+    fn sampler(&self, texture: &Value) -> Sampler {
+        let mut sampler = Sampler::default();
+        let json = match texture.as_u64() {
+            Some(index) => match self.json["textures"][index as usize]["sampler"].as_u64() {
+                Some(index) => &self.json["samplers"][index as usize],
+                None => return sampler,
+            },
+            None => return sampler,
+        };
+
+        sampler.filter = match json["minFilter"].as_u64() {
+            Some(9728) | Some(9984) => ImageFilter::Nearest,
+            Some(9986) | Some(9987) => ImageFilter::Trilinear,
+            _ => ImageFilter::Bilinear,
+        };
+        // both directions share a single wrap mode
+        sampler.wrap = match json["wrapS"].as_u64() {
+            Some(33071) => ImageWrap::Clamp,
+            Some(33648) => ImageWrap::Mirror,
+            _ => ImageWrap::Repeat,
+        };
+        sampler
+    }
+
+    fn material(&self, index: &Value) -> Texture {
+        let mut texture = Texture::default();
+        let material = match index.as_u64() {
+            Some(index) => &self.json["materials"][index as usize],
+            None => return texture,
+        };
+        let pbr = &material["pbrMetallicRoughness"];
+        let factor = |index: usize| pbr["baseColorFactor"][index].as_f64().unwrap_or(1.0);
+
+        texture.color = Color {
+            r: factor(0) * 255.0,
+            g: factor(1) * 255.0,
+            b: factor(2) * 255.0,
+        };
+        if material["alphaMode"].as_str() == Some("BLEND") {
+            texture.alpha = factor(3);
+        }
+        texture.shading = Shading::Pbr;
+        texture.metalness = pbr["metallicFactor"].as_f64().unwrap_or(1.0);
+        texture.roughness = pbr["roughnessFactor"].as_f64().unwrap_or(1.0);
+        if let Some(image) = self.image(&pbr["baseColorTexture"]["index"]) {
+            texture.texture_type = TexturesTypes::Image as u64;
+            texture.image = image;
+            texture.sampler = self.sampler(&pbr["baseColorTexture"]["index"]);
+        }
+        let extensions = &material["extensions"];
+        texture.ior = extensions["KHR_materials_ior"]["ior"]
+            .as_f64()
+            .unwrap_or(texture.ior);
+        texture.transmission = extensions["KHR_materials_transmission"]["transmissionFactor"]
+            .as_f64()
+            .unwrap_or(texture.transmission);
+        if material["emissiveFactor"].is_array() {
+            texture.emission = Color {
+                r: material["emissiveFactor"][0].as_f64().unwrap_or(0.0) * 255.0,
+                g: material["emissiveFactor"][1].as_f64().unwrap_or(0.0) * 255.0,
+                b: material["emissiveFactor"][2].as_f64().unwrap_or(0.0) * 255.0,
+            };
+            texture.emission_strength = extensions["KHR_materials_emissive_strength"]
+                ["emissiveStrength"]
+                .as_f64()
+                .unwrap_or(1.0);
+        }
+        let volume = &extensions["KHR_materials_volume"];
+        if volume["attenuationColor"].is_array() {
+            texture.absorption = Color {
+                r: volume["attenuationColor"][0].as_f64().unwrap_or(1.0) * 255.0,
+                g: volume["attenuationColor"][1].as_f64().unwrap_or(1.0) * 255.0,
+                b: volume["attenuationColor"][2].as_f64().unwrap_or(1.0) * 255.0,
+            };
+        }
+        texture.absorption_distance = volume["attenuationDistance"]
+            .as_f64()
+            .unwrap_or(f64::INFINITY);
+        texture
+    }
+}
+
+fn identity() -> Matrix {
+    let mut result = Matrix::new(4, 4);
+
+    for i in 0..4 {
+        result.data[i][i] = 1.0;
+    }
+    result
+}
+
+fn local_matrix(node: &Value) -> Matrix {
+    let mut result = identity();
+
+    if let Some(values) = node["matrix"].as_array() {
+        // column major
+        for (index, value) in values.iter().take(16).enumerate() {
+            result.data[index % 4][index / 4] = value.as_f64().unwrap_or(0.0);
+        }
+        return result;
+    }
+    let value =
+        |name: &str, index: usize, default: f64| node[name][index].as_f64().unwrap_or(default);
+    let (x, y, z, w) = (
+        value("rotation", 0, 0.0),
+        value("rotation", 1, 0.0),
+        value("rotation", 2, 0.0),
+        value("rotation", 3, 1.0),
+    );
+    let rotation = [
+        [
+            1.0 - 2.0 * (y * y + z * z),
+            2.0 * (x * y - z * w),
+            2.0 * (x * z + y * w),
+        ],
+        [
+            2.0 * (x * y + z * w),
+            1.0 - 2.0 * (x * x + z * z),
+            2.0 * (y * z - x * w),
+        ],
+        [
+            2.0 * (x * z - y * w),
+            2.0 * (y * z + x * w),
+            1.0 - 2.0 * (x * x + y * y),
+        ],
+    ];
+    for (row, rotation_row) in rotation.iter().enumerate() {
+        for (column, rotation_value) in rotation_row.iter().enumerate() {
+            result.data[row][column] = rotation_value * value("scale", column, 1.0);
+        }
+        result.data[row][3] = value("translation", row, 0.0);
+    }
+    result
+}
+
+/// glTF is Y up while the renderer is Z up
+fn to_renderer_space(vector: Vector) -> Vector {
+    Vector {
+        x: vector.x,
+        y: -vector.z,
+        z: vector.y,
+    }
+}
+
+fn transform_point(matrix: &Matrix, point: &[f64]) -> Vector {
+    let row = |index: usize| {
+        matrix.data[index][0] * point[0]
+            + matrix.data[index][1] * point[1]
+            + matrix.data[index][2] * point[2]
+            + matrix.data[index][3]
+    };
+
+    to_renderer_space(Vector {
+        x: row(0),
+        y: row(1),
+        z: row(2),
+    })
+}
+
+fn transform_direction(matrix: &Matrix, direction: &[f64]) -> Vector {
+    let row = |index: usize| {
+        matrix.data[index][0] * direction[0]
+            + matrix.data[index][1] * direction[1]
+            + matrix.data[index][2] * direction[2]
+    };
+
+    to_renderer_space(Vector {
+        x: row(0),
+        y: row(1),
+        z: row(2),
+    })
+    .normalize()
+}
+
+/// Transposed inverse of the upper 3x3 part, up to a positive factor, used for normals
+fn normal_matrix(matrix: &Matrix) -> Matrix {
+    let m = |row: usize, column: usize| matrix.data[row % 3][column % 3];
+    let mut result = identity();
+
+    for row in 0..3 {
+        for column in 0..3 {
+            result.data[row][column] = m(row + 1, column + 1) * m(row + 2, column + 2)
+                - m(row + 1, column + 2) * m(row + 2, column + 1);
+        }
+    }
+    let determinant: f64 = (0..3).map(|i| m(0, i) * result.data[0][i]).sum();
+    for row in 0..3 {
+        for column in 0..3 {
+            result.data[row][column] *= determinant.signum();
+        }
+        result.data[row][3] = 0.0;
+    }
+    result
+}
+
+/// Euler angles in degrees matching `Vector::rotate` for an orthonormal basis
+fn euler_angles(right: Vector, forward: Vector, up: Vector) -> Vector {
+    Vector {
+        x: forward.z.atan2(up.z).to_degrees(),
+        y: (-right.z).clamp(-1.0, 1.0).asin().to_degrees(),
+        z: right.y.atan2(right.x).to_degrees(),
+    }
+}
+
+impl GltfScene {
+    pub fn open(file: &str) -> Option<GltfScene> {
+        let document = Document::open(file)?;
+        let mut scene = GltfScene {
+            primitives: Vec::new(),
+            lights: Vec::new(),
+            camera: None,
+        };
+        let roots: Vec<u64> = match document.json["scenes"]
+            [document.json["scene"].as_u64().unwrap_or(0) as usize]["nodes"]
+            .as_array()
+        {
+            Some(nodes) => nodes.iter().filter_map(|node| node.as_u64()).collect(),
+            None => (0..document.json["nodes"]
+                .as_array()
+                .map_or(0, |nodes| nodes.len()) as u64)
+                .collect(),
+        };
+
+        for root in roots {
+            let node = scene.get_node(&document, root as usize, &identity(), 0);
+            scene.primitives.push(node);
+        }
+        Some(scene)
+    }
+
+    fn get_node(
+        &mut self,
+        document: &Document,
+        index: usize,
+        parent: &Matrix,
+        depth: usize,
+    ) -> Box<dyn Object + Send + Sync> {
+        let node = &document.json["nodes"][index];
+        let world = parent.multiply(&local_matrix(node));
+        let mut children: Vec<Box<dyn Object + Send + Sync>> = Vec::new();
+
+        if let Some(mesh) = node["mesh"].as_u64() {
+            if let Some(mesh) = self.get_mesh(document, mesh as usize, &world) {
+                children.push(mesh);
+            }
+        }
+        if let Some(camera) = node["camera"].as_u64() {
+            self.get_camera(document, camera as usize, &world);
+        }
+        if let Some(light) = node["extensions"]["KHR_lights_punctual"]["light"].as_u64() {
+            self.get_light(document, light as usize, &world);
+        }
+        // a node can't be its own ancestor, deeper hierarchies are malformed
+        if depth
+            < document.json["nodes"]
+                .as_array()
+                .map_or(0, |nodes| nodes.len())
+        {
+            for child in node["children"].as_array().unwrap_or(&Vec::new()) {
+                if let Some(child) = child.as_u64() {
+                    children.push(self.get_node(document, child as usize, &world, depth + 1));
+                }
+            }
+        }
+        Box::new(Parent {
+            name: node["name"].as_str().unwrap_or("Nan").to_string(),
+            obj_type: "parent".to_string(),
+            transform: Transform::default(),
+            children,
+        })
+    }
+
+    fn get_mesh(
+        &self,
+        document: &Document,
+        index: usize,
+        world: &Matrix,
+    ) -> Option<Box<dyn Object + Send + Sync>> {
+        let json = &document.json["meshes"][index];
+        let normals_matrix = normal_matrix(world);
+        let mut mesh = Mesh {
+            name: json["name"].as_str().unwrap_or("Nan").to_string(),
+            obj_type: "mesh".to_string(),
+            transform: Transform::default(),
+            texture: Texture::default(),
+            normal_map: Texture::normal_map_default(),
+            triangles: Vec::new(),
+            bvh: Bvh::default(),
+            children: Vec::new(),
+        };
+
+        for primitive in json["primitives"].as_array()? {
+            if primitive["mode"].as_u64().unwrap_or(TRIANGLES_MODE) != TRIANGLES_MODE {
+                continue;
+            }
+            let attributes = &primitive["attributes"];
+            let positions = document.accessor(&attributes["POSITION"])?;
+            let normals = document.accessor(&attributes["NORMAL"]);
+            let uvs = document.accessor(&attributes["TEXCOORD_0"]);
+            let indices: Vec<usize> = match document.accessor(&primitive["indices"]) {
+                Some(indices) => indices.iter().map(|index| index[0] as usize).collect(),
+                None => (0..positions.len()).collect(),
+            };
+            let texture = document.material(&primitive["material"]);
+            let corner = |index: usize| {
+                Some((
+                    transform_point(world, positions.get(index)?),
+                    normals
+                        .as_ref()
+                        .and_then(|normals| normals.get(index))
+                        .map(|normal| transform_direction(&normals_matrix, normal)),
+                    // glTF puts the origin of the texture at the top left corner
+                    // while the renderer reads images from the bottom right one
+                    uvs.as_ref()
+                        .and_then(|uvs| uvs.get(index))
+                        .map(|uv| Vector {
+                            x: 1.0 - uv[0],
+                            y: 1.0 - uv[1],
+                            z: 0.0,
+                        }),
+                ))
+            };
+
+            for face in indices.chunks_exact(3) {
+                let triangle = [corner(face[0])?, corner(face[1])?, corner(face[2])?];
+                mesh.triangles.push(mesh.make_triangle(triangle, &texture));
+            }
+        }
+        mesh.apply_transform();
+        Some(Box::new(mesh))
+    }
+
+    fn get_camera(&mut self, document: &Document, index: usize, world: &Matrix) {
+        let json = &document.json["cameras"][index];
+
+        if self.camera.is_some() || json["type"].as_str() != Some("perspective") {
+            return;
+        }
+        // the camera looks along -Z with Y up, the renderer one along +Y with Z up
+        let right = transform_direction(world, &[1.0, 0.0, 0.0]);
+        let up = transform_direction(world, &[0.0, 1.0, 0.0]);
+        let forward = transform_direction(world, &[0.0, 0.0, -1.0]);
+
+        self.camera = Some(GltfCamera {
+            transform: Transform {
+                pos: transform_point(world, &[0.0, 0.0, 0.0]),
+                rotation: euler_angles(right, forward, up),
+                scale: 1.0,
+            },
+            yfov: json["perspective"]["yfov"].as_f64().unwrap_or(0.8),
+        });
+    }
+
+    fn get_light(&mut self, document: &Document, index: usize, world: &Matrix) {
+        let json = &document.json["extensions"]["KHR_lights_punctual"]["lights"][index];
+        let factor = |index: usize| json["color"][index].as_f64().unwrap_or(1.0);
+        let color = Color {
+            r: factor(0) * 255.0,
+            g: factor(1) * 255.0,
+            b: factor(2) * 255.0,
+        };
+        let strength = json["intensity"].as_f64().unwrap_or(1.0);
+
+        match json["type"].as_str() {
+            Some("directional") => self.lights.push(Box::new(Directional {
+                transform: Transform {
+                    // direction towards the light
+                    pos: transform_direction(world, &[0.0, 0.0, 1.0]),
+                    rotation: Vector {
+                        x: 0.0,
+                        y: 0.0,
+                        z: 0.0,
+                    },
+                    scale: 1.0,
+                },
+                color,
+                strength,
+                visible: false,
+            })),
+            Some("spot") => {
+                // spots shine down their local -z axis
+                let direction = transform_direction(world, &[0.0, 0.0, -1.0]).normalize();
+                let spread = (direction.x * direction.x + direction.y * direction.y).sqrt();
+                self.lights.push(Box::new(Spot {
+                    transform: Transform {
+                        pos: transform_point(world, &[0.0, 0.0, 0.0]),
+                        rotation: Vector {
+                            x: spread.atan2(-direction.z).to_degrees(),
+                            y: 0.0,
+                            z: (-direction.x).atan2(direction.y).to_degrees(),
+                        },
+                        scale: 1.0,
+                    },
+                    color,
+                    strength,
+                    radius: 0.1,
+                    falloff: 2,
+                    inner_angle: json["spot"]["innerConeAngle"]
+                        .as_f64()
+                        .unwrap_or(0.0)
+                        .to_degrees(),
+                    outer_angle: json["spot"]["outerConeAngle"]
+                        .as_f64()
+                        .unwrap_or(std::f64::consts::FRAC_PI_4)
+                        .to_degrees(),
+                    visible: false,
+                }))
+            }
+            Some(_) => self.lights.push(Box::new(Point {
+                transform: Transform {
+                    pos: transform_point(world, &[0.0, 0.0, 0.0]),
+                    rotation: Vector {
+                        x: 0.0,
+                        y: 0.0,
+                        z: 0.0,
+                    },
+                    scale: 1.0,
+                },
+                color,
+                strength,
+                radius: 0.1,
+                falloff: 2,
+                visible: false,
+            })),
+            None => {}
+        }
+    }
+}
@@ -5,11 +5,14 @@
 // lights
 //
 
+use super::brdf;
+use super::bvh::Bvh;
 use super::camera::Camera;
 use super::primitives::{Intersection, Object};
-use super::types::{Color, Transform};
+use super::types::{Color, Shading, Transform};
 use crate::vector::{resolve_quadratic_equation, Vector};
 use erased_serde::serialize_trait_object;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize)]
@@ -30,6 +33,266 @@ pub struct Directional {
     pub visible: bool,
 }
 
+/// Point light restricted to a cone, it shines toward -z before the
+/// rotation of its transform is applied
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct Spot {
+    pub transform: Transform,
+    pub color: Color,
+    pub strength: f64,
+    pub radius: f64,
+    pub falloff: i32,
+    /// Half angle in degrees of the fully lit part of the cone
+    pub inner_angle: f64,
+    /// Half angle in degrees past which the light is gone
+    pub outer_angle: f64,
+    pub visible: bool,
+}
+
+impl Spot {
+    pub fn direction(&self) -> Vector {
+        let mut direction = Vector {
+            x: 0.0,
+            y: 0.0,
+            z: -1.0,
+        };
+        direction.rotate(
+            self.transform.rotation.x,
+            self.transform.rotation.y,
+            self.transform.rotation.z,
+        );
+        direction
+    }
+
+    /// Smooth attenuation from the inner to the outer edge of the cone
+    fn cone_attenuation(&self, light_vector: Vector) -> f64 {
+        let cos_angle = (light_vector * -1.0).dot_product(self.direction().normalize());
+        let cos_inner = self.inner_angle.to_radians().cos();
+        let cos_outer = self.outer_angle.to_radians().cos();
+        if cos_inner - cos_outer <= 0.0 {
+            return if cos_angle >= cos_outer { 1.0 } else { 0.0 };
+        }
+
+        ((cos_angle - cos_outer) / (cos_inner - cos_outer))
+            .clamp(0.0, 1.0)
+            .powi(2)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum AreaShape {
+    Rectangle { width: f64, height: f64 },
+    Disk { radius: f64 },
+}
+
+/// Flat light emitting from its front face, centered on its position and
+/// facing toward -z before the rotation of its transform is applied
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct Area {
+    pub transform: Transform,
+    pub color: Color,
+    pub strength: f64,
+    pub shape: AreaShape,
+    pub visible: bool,
+}
+
+/// Point on an area light with the solid angle density it was drawn with
+struct AreaSample {
+    point: Vector,
+    pdf: f64,
+}
+
+impl Area {
+    /// Unit vectors along the width and the height of the light, and the
+    /// direction it shines toward
+    fn axes(&self) -> (Vector, Vector, Vector) {
+        let mut axes = [
+            Vector {
+                x: 1.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            Vector {
+                x: 0.0,
+                y: 1.0,
+                z: 0.0,
+            },
+            Vector {
+                x: 0.0,
+                y: 0.0,
+                z: -1.0,
+            },
+        ];
+        for axis in axes.iter_mut() {
+            axis.rotate(
+                self.transform.rotation.x,
+                self.transform.rotation.y,
+                self.transform.rotation.z,
+            );
+        }
+        (axes[0], axes[1], axes[2])
+    }
+
+    /// Draws a point uniformly in the solid angle the rectangle covers seen
+    /// from `origin` (Urena et al., "An area-preserving parametrization for
+    /// spherical rectangles")
+    fn sample_rectangle(
+        &self,
+        origin: Vector,
+        width: f64,
+        height: f64,
+        random: (f64, f64),
+    ) -> Option<AreaSample> {
+        let (x, y, mut z) = self.axes();
+        let corner = self.transform.pos - x * (width / 2.0) - y * (height / 2.0);
+        let to_corner = corner - origin;
+        let (x0, y0, mut z0) = (
+            to_corner.dot_product(x),
+            to_corner.dot_product(y),
+            to_corner.dot_product(z),
+        );
+        if z0.abs() < 1e-9 {
+            return None;
+        }
+        if z0 > 0.0 {
+            z = z * -1.0;
+            z0 = -z0;
+        }
+        let (x1, y1) = (x0 + width, y0 + height);
+        let normal = |a: f64, b: f64, c: f64| Vector { x: a, y: b, z: c }.normalize();
+        let n0 = normal(0.0, z0, -y0);
+        let n1 = normal(-z0, 0.0, x1);
+        let n2 = normal(0.0, -z0, y1);
+        let n3 = normal(z0, 0.0, -x0);
+        let g0 = (-n0.dot_product(n1)).clamp(-1.0, 1.0).acos();
+        let g1 = (-n1.dot_product(n2)).clamp(-1.0, 1.0).acos();
+        let g2 = (-n2.dot_product(n3)).clamp(-1.0, 1.0).acos();
+        let g3 = (-n3.dot_product(n0)).clamp(-1.0, 1.0).acos();
+        let k = std::f64::consts::TAU - g2 - g3;
+        let solid_angle = g0 + g1 - k;
+        if solid_angle <= 1e-12 {
+            return None;
+        }
+
+        let angle = random.0 * solid_angle + k;
+        let fu = (angle.cos() * n0.z - n2.z) / angle.sin();
+        let cu = ((1.0 / (fu * fu + n0.z * n0.z).sqrt()) * fu.signum()).clamp(-1.0, 1.0);
+        let xu = (-(cu * z0) / (1.0 - cu * cu).max(1e-12).sqrt()).clamp(x0, x1);
+        let distance = (xu * xu + z0 * z0).sqrt();
+        let h0 = y0 / (distance * distance + y0 * y0).sqrt();
+        let h1 = y1 / (distance * distance + y1 * y1).sqrt();
+        let hv = h0 + random.1 * (h1 - h0);
+        let yv = if hv * hv < 1.0 - 1e-9 {
+            (hv * distance) / (1.0 - hv * hv).sqrt()
+        } else {
+            y1
+        };
+
+        Some(AreaSample {
+            point: origin + x * xu + y * yv + z * z0,
+            pdf: 1.0 / solid_angle,
+        })
+    }
+
+    /// Draws a point uniformly on the disk, concentric mapping keeps the
+    /// strata of the square, the density is brought back to solid angle
+    fn sample_disk(&self, origin: Vector, radius: f64, random: (f64, f64)) -> Option<AreaSample> {
+        let (x, y, z) = self.axes();
+        let (a, b) = (random.0 * 2.0 - 1.0, random.1 * 2.0 - 1.0);
+        let (r, theta) = if a == 0.0 && b == 0.0 {
+            (0.0, 0.0)
+        } else if a.abs() > b.abs() {
+            (a, std::f64::consts::FRAC_PI_4 * (b / a))
+        } else {
+            (
+                b,
+                std::f64::consts::FRAC_PI_2 - std::f64::consts::FRAC_PI_4 * (a / b),
+            )
+        };
+        let point =
+            self.transform.pos + x * (r * radius * theta.cos()) + y * (r * radius * theta.sin());
+        let to_light = point - origin;
+        let cos_light = (to_light.normalize() * -1.0).dot_product(z).abs();
+        if cos_light < 1e-9 {
+            return None;
+        }
+
+        Some(AreaSample {
+            point,
+            pdf: to_light.len2() / (std::f64::consts::PI * radius * radius * cos_light),
+        })
+    }
+
+    fn sample_point(&self, origin: Vector, random: (f64, f64)) -> Option<AreaSample> {
+        match self.shape {
+            AreaShape::Rectangle { width, height } => {
+                self.sample_rectangle(origin, width, height, random)
+            }
+            AreaShape::Disk { radius } => self.sample_disk(origin, radius, random),
+        }
+    }
+}
+
+/// Jittered points spread over the unit square, one in each cell of the
+/// smallest grid holding `count` of them
+pub fn stratified_samples(count: usize) -> Vec<(f64, f64)> {
+    let side = (count as f64).sqrt().ceil() as usize;
+    let mut rng = rand::thread_rng();
+    let mut samples = Vec::with_capacity(side * side);
+
+    for i in 0..side {
+        for j in 0..side {
+            samples.push((
+                (i as f64 + rng.gen_range(0.0..1.0)) / side as f64,
+                (j as f64 + rng.gen_range(0.0..1.0)) / side as f64,
+            ));
+        }
+    }
+    samples
+}
+
+/// Direction toward a light drawn by the path tracer, with the light it
+/// carries
+pub struct LightSample {
+    pub direction: Vector,
+    /// Distance to the point drawn, infinite for lights with no position
+    pub distance: f64,
+    pub radiance: Vector,
+    /// Solid angle density of the direction, zero for lights reduced to a
+    /// point or a direction whose radiance is the light reaching a surface
+    /// facing them
+    pub pdf: f64,
+}
+
+/// Whether a hit landed on this very light
+fn hit_on(hit: &Intersection, light: &dyn Light) -> bool {
+    hit.light
+        .is_some_and(|other| std::ptr::addr_eq(other as *const dyn Light, light))
+}
+
+/// Glowing part of a primitive, sampled as an area light through the sphere
+/// bounding it
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct Emissive {
+    pub index: usize,
+    pub transform: Transform,
+    pub radius: f64,
+}
+
+impl Emissive {
+    /// Cosine of the half angle of the cone holding the bounding sphere
+    /// seen from `origin`, every direction counts from inside of it
+    fn cone(&self, origin: Vector) -> f64 {
+        let to_center = self.transform.pos - origin;
+
+        if to_center.len() > self.radius {
+            (1.0 - self.radius.powi(2) / to_center.len2()).sqrt()
+        } else {
+            -1.0
+        }
+    }
+}
+
 pub trait Light: erased_serde::Serialize {
     fn move_obj(&mut self, offset: Transform);
     fn get_transform(&self) -> Transform;
@@ -44,21 +307,216 @@ pub trait Light: erased_serde::Serialize {
     fn get_falloff(&self) -> i32;
     fn set_falloff(&mut self, new: i32);
     fn intersection(&self, ray: Vector, origin: Vector) -> Option<Intersection>;
-    fn light_is_intersected(
+    /// Light reaching the surface along `light_vector` for each channel,
+    /// dimmed and tinted by the transparent objects it goes through
+    fn light_transmittance(
         &self,
         light_vector: Vector,
         intersect: &Intersection,
         normal_vector: Vector,
         camera: Camera,
         primitives: &[Box<dyn Object + Send + Sync>],
-    ) -> bool;
+        bvh: &Bvh,
+    ) -> Vector;
     fn calculate_light(
         &self,
         intersect: &Intersection,
         camera_to_pixel: Vector,
         camera: Camera,
         primitives: &[Box<dyn Object + Send + Sync>],
+        bvh: &Bvh,
     ) -> Vector;
+    fn sample(
+        &self,
+        origin: Vector,
+        random: (f64, f64),
+        camera: Camera,
+        primitives: &[Box<dyn Object + Send + Sync>],
+    ) -> Option<LightSample>;
+    /// Light given off toward `origin` where a path hit the light, with the
+    /// density `sample` draws that direction with
+    fn emission(
+        &self,
+        origin: Vector,
+        hit: &Intersection,
+        primitives: &[Box<dyn Object + Send + Sync>],
+    ) -> Option<(Vector, f64)>;
+}
+
+/// Light reflected toward the camera by a surface lit from `light_vector`
+/// by a white light of unit strength, with the shading model of its material
+pub fn surface_response(
+    intersect: &Intersection,
+    light_vector: Vector,
+    camera_to_pixel: Vector,
+    camera: Camera,
+) -> Vector {
+    let normal_vector = intersect.normal.normalize();
+    let view = (camera_to_pixel * -1.0).normalize();
+    let texture = intersect.object.unwrap().get_texture();
+    let texture_coordinates = intersect.object.unwrap().surface_position(
+        intersect.intersection_point - intersect.object.unwrap().get_transform().pos,
+    );
+    let albedo = texture
+        .sample(
+            texture_coordinates.x,
+            texture_coordinates.y,
+            intersect.footprint,
+        )
+        .as_vector();
+
+    if texture.shading == Shading::Pbr {
+        return brdf::cook_torrance(
+            albedo,
+            &texture,
+            normal_vector,
+            view,
+            light_vector.normalize(),
+        );
+    }
+    let diffuse =
+        light_vector.dot_product(normal_vector).max(0.0) * camera.diffuse * texture.diffuse;
+    let reflected = light_vector.reflect(normal_vector).normalize();
+    let specular = camera.specular
+        * texture.specular
+        * reflected.dot_product(view).max(0.0).powf(texture.shininess);
+
+    albedo * diffuse + specular
+}
+
+/// Share of the light an occluder lets through, what its alpha leaves
+/// uncovered plus what its transmission lets in, tinted by its color and
+/// absorbed along the way to its other side. Refracted light is left to the
+/// photons once the camera shoots caustics
+fn occluder_transmittance(hit: &Intersection, light_vector: Vector, camera: Camera) -> Vector {
+    let object = hit.object.unwrap();
+    let texture = object.get_texture();
+    let mut transmitted = Vector {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    };
+
+    if texture.transmission > 0.0 && camera.caustic_photons == 0 {
+        let texture_coordinates =
+            object.surface_position(hit.intersection_point - object.get_transform().pos);
+        let color = texture
+            .texture(texture_coordinates.x, texture_coordinates.y)
+            .as_vector();
+        let inside = hit.intersection_point + light_vector * camera.shadow_bias;
+        let crossed = object
+            .intersection(light_vector, inside)
+            .map_or(0.0, |exit| (exit.intersection_point - inside).len());
+        transmitted = color
+            * super::Medium::from_texture(&texture).transmittance(crossed)
+            * texture.transmission;
+    }
+    transmitted * texture.alpha + (1.0 - texture.alpha)
+}
+
+/// Light let through by the primitives standing closer than `light_distance`
+/// from `origin` along `light_vector`, `skip` is a primitive giving off the
+/// light itself
+fn shadow_transmittance(
+    origin: Vector,
+    light_vector: Vector,
+    light_distance: f64,
+    skip: Option<usize>,
+    camera: Camera,
+    primitives: &[Box<dyn Object + Send + Sync>],
+    bvh: &Bvh,
+) -> Vector {
+    let mut transmittance = Vector {
+        x: 1.0,
+        y: 1.0,
+        z: 1.0,
+    };
+
+    bvh.any_intersection(light_vector, origin, light_distance, |index| {
+        if skip == Some(index) {
+            return false;
+        }
+        match primitives[index].intersection(light_vector, origin) {
+            Some(shadow_intersect)
+                if (shadow_intersect.intersection_point - origin).len() < light_distance =>
+            {
+                transmittance =
+                    transmittance * occluder_transmittance(&shadow_intersect, light_vector, camera);
+                // nothing gets through anymore, no need to look further
+                transmittance.len2() == 0.0
+            }
+            _ => false,
+        }
+    });
+    transmittance
+}
+
+/// Light let through by what stands between the surface and a light at
+/// `position`
+fn position_transmittance(
+    position: Vector,
+    light_vector: Vector,
+    intersect: &Intersection,
+    normal_vector: Vector,
+    camera: Camera,
+    primitives: &[Box<dyn Object + Send + Sync>],
+    bvh: &Bvh,
+) -> Vector {
+    let origin = intersect.intersection_point + (normal_vector * camera.shadow_bias);
+    let light_distance = (position - intersect.intersection_point).len();
+
+    shadow_transmittance(
+        origin,
+        light_vector,
+        light_distance,
+        None,
+        camera,
+        primitives,
+        bvh,
+    )
+}
+
+/// Share of a light reaching the surface for each channel, its sphere is
+/// sampled when the camera asks for smooth shadows
+fn light_uncovered(
+    light: &dyn Light,
+    light_vector: Vector,
+    intersect: &Intersection,
+    normal_vector: Vector,
+    camera: Camera,
+    primitives: &[Box<dyn Object + Send + Sync>],
+    bvh: &Bvh,
+) -> Vector {
+    if !camera.smooth_shadow {
+        return light.light_transmittance(
+            light_vector,
+            intersect,
+            normal_vector,
+            camera,
+            primitives,
+            bvh,
+        );
+    }
+    let mut light_reached = Vector {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    };
+    for _ in 0..camera.smooth_shadow_step {
+        let inter_to_light = light.get_transform().pos
+            + Vector::get_random_point_in_sphere(light.get_radius())
+            - intersect.intersection_point;
+        light_reached = light_reached
+            + light.light_transmittance(
+                inter_to_light.normalize(),
+                intersect,
+                normal_vector,
+                camera,
+                primitives,
+                bvh,
+            );
+    }
+    light_reached / camera.smooth_shadow_step as f64
 }
 
 impl Light for Point {
@@ -134,36 +592,32 @@ impl Light for Point {
                 intersection_point: point,
                 object: None,
                 light: Some(self),
+                footprint: 0.0,
+                primitive: None,
             })
         } else {
             None
         }
     }
 
-    fn light_is_intersected(
+    fn light_transmittance(
         &self,
         light_vector: Vector,
         intersect: &Intersection,
         normal_vector: Vector,
         camera: Camera,
         primitives: &[Box<dyn Object + Send + Sync>],
-    ) -> bool {
-        for object_current in primitives.iter() {
-            match object_current.intersection(
-                light_vector,
-                intersect.intersection_point + (normal_vector * camera.shadow_bias),
-            ) {
-                None => continue,
-                Some(shadow_intersect) => {
-                    if (shadow_intersect.intersection_point - intersect.intersection_point).len()
-                        < (self.transform.pos - intersect.intersection_point).len()
-                    {
-                        return true;
-                    }
-                }
-            }
-        }
-        false
+        bvh: &Bvh,
+    ) -> Vector {
+        position_transmittance(
+            self.transform.pos,
+            light_vector,
+            intersect,
+            normal_vector,
+            camera,
+            primitives,
+            bvh,
+        )
     }
 
     fn calculate_light(
@@ -172,68 +626,63 @@ impl Light for Point {
         camera_to_pixel: Vector,
         camera: Camera,
         primitives: &[Box<dyn Object + Send + Sync>],
+        bvh: &Bvh,
     ) -> Vector {
         let normal_vector = intersect.normal.normalize();
         let light_vector = (self.get_transform().pos - intersect.intersection_point).normalize();
-        let mut light_uncovered = 1.0;
-
-        if !camera.smooth_shadow {
-            if self.light_is_intersected(light_vector, intersect, normal_vector, camera, primitives)
-            {
-                return Vector {
-                    x: 0.0,
-                    y: 0.0,
-                    z: 0.0,
-                };
-            }
-        } else {
-            let mut light_reached: i16 = 0;
-            for _ in 0..camera.smooth_shadow_step {
-                let inter_to_light = self.get_transform().pos
-                    + Vector::get_random_point_in_sphere(self.get_radius())
-                    - intersect.intersection_point;
-                if !self.light_is_intersected(
-                    inter_to_light.normalize(),
-                    intersect,
-                    normal_vector,
-                    camera,
-                    primitives,
-                ) {
-                    light_reached += 1;
-                }
-            }
-            light_uncovered = light_reached as f64 / camera.smooth_shadow_step as f64;
-        }
-        let diffuse = light_vector.dot_product(normal_vector).max(0.0)
-            * camera.diffuse
-            * intersect.object.unwrap().get_texture().diffuse;
-
-        let reflected = light_vector.reflect(normal_vector).normalize();
-        let view = (camera_to_pixel * -1.0).normalize();
-        let specular = camera.specular
-            * intersect.object.unwrap().get_texture().specular
-            * reflected
-                .dot_product(view)
-                .max(0.0)
-                .powf(intersect.object.unwrap().get_texture().shininess);
+        let light_uncovered = light_uncovered(
+            self,
+            light_vector,
+            intersect,
+            normal_vector,
+            camera,
+            primitives,
+            bvh,
+        );
         let distance = intersect
             .intersection_point
             .distance(self.get_transform().pos);
         let light_falloff = (self.get_strength() / distance.powi(self.get_falloff())).max(0.0);
-        let texture_coordinates = intersect.object.unwrap().surface_position(
-            intersect.intersection_point - intersect.object.unwrap().get_transform().pos,
-        );
-        intersect
-            .object
-            .unwrap()
-            .get_texture()
-            .texture(texture_coordinates.x, texture_coordinates.y)
-            .as_vector()
+
+        surface_response(intersect, light_vector, camera_to_pixel, camera)
             * self.get_color().as_vector()
-            * diffuse
             * light_falloff
             * light_uncovered
-            + self.get_color().as_vector() * specular * light_falloff * light_uncovered
+    }
+
+    fn sample(
+        &self,
+        origin: Vector,
+        _random: (f64, f64),
+        camera: Camera,
+        _primitives: &[Box<dyn Object + Send + Sync>],
+    ) -> Option<LightSample> {
+        let mut position = self.transform.pos;
+        if camera.smooth_shadow {
+            position = position + Vector::get_random_point_in_sphere(self.radius);
+        }
+        let to_light = position - origin;
+        let distance = origin.distance(self.transform.pos);
+
+        Some(LightSample {
+            direction: to_light.normalize(),
+            distance: to_light.len(),
+            radiance: self.color.as_vector()
+                * (self.strength / distance.powi(self.falloff)).max(0.0),
+            pdf: 0.0,
+        })
+    }
+
+    fn emission(
+        &self,
+        _origin: Vector,
+        hit: &Intersection,
+        _primitives: &[Box<dyn Object + Send + Sync>],
+    ) -> Option<(Vector, f64)> {
+        if !hit_on(hit, self) {
+            return None;
+        }
+        Some((self.color.as_vector(), 0.0))
     }
 }
 
@@ -304,35 +753,204 @@ impl Light for Directional {
                 intersection_point: point,
                 object: None,
                 light: Some(self),
+                footprint: 0.0,
+                primitive: None,
             });
         }
         None
     }
 
-    fn light_is_intersected(
+    fn light_transmittance(
         &self,
         light_vector: Vector,
         intersect: &Intersection,
         normal_vector: Vector,
         camera: Camera,
         primitives: &[Box<dyn Object + Send + Sync>],
-    ) -> bool {
-        for object_current in primitives.iter() {
-            match object_current.intersection(
-                light_vector,
-                intersect.intersection_point + (normal_vector * camera.shadow_bias),
-            ) {
-                None => continue,
-                Some(shadow_intersect) => {
-                    if (shadow_intersect.intersection_point - intersect.intersection_point).len()
-                        < (self.transform.pos - intersect.intersection_point).len()
-                    {
-                        return true;
-                    }
-                }
+        bvh: &Bvh,
+    ) -> Vector {
+        position_transmittance(
+            self.transform.pos,
+            light_vector,
+            intersect,
+            normal_vector,
+            camera,
+            primitives,
+            bvh,
+        )
+    }
+
+    fn calculate_light(
+        &self,
+        intersect: &Intersection,
+        camera_to_pixel: Vector,
+        camera: Camera,
+        primitives: &[Box<dyn Object + Send + Sync>],
+        bvh: &Bvh,
+    ) -> Vector {
+        let normal_vector = intersect.normal.normalize();
+        let light_uncovered = if !camera.smooth_shadow {
+            self.light_transmittance(
+                self.transform.pos,
+                intersect,
+                normal_vector,
+                camera,
+                primitives,
+                bvh,
+            )
+        } else {
+            let mut light_reached = Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            };
+            for _ in 0..camera.smooth_shadow_step {
+                let inter_to_light = self.transform.pos + Vector::get_random_point_in_sphere(0.0)
+                    - intersect.intersection_point;
+                light_reached = light_reached
+                    + self.light_transmittance(
+                        inter_to_light.normalize(),
+                        intersect,
+                        normal_vector,
+                        camera,
+                        primitives,
+                        bvh,
+                    );
             }
+            light_reached / camera.smooth_shadow_step as f64
+        };
+        surface_response(intersect, self.transform.pos, camera_to_pixel, camera)
+            * self.get_color().as_vector()
+            * light_uncovered
+    }
+
+    fn sample(
+        &self,
+        _origin: Vector,
+        _random: (f64, f64),
+        _camera: Camera,
+        _primitives: &[Box<dyn Object + Send + Sync>],
+    ) -> Option<LightSample> {
+        Some(LightSample {
+            direction: self.transform.pos.normalize(),
+            distance: f64::INFINITY,
+            radiance: self.color.as_vector(),
+            pdf: 0.0,
+        })
+    }
+
+    fn emission(
+        &self,
+        _origin: Vector,
+        hit: &Intersection,
+        _primitives: &[Box<dyn Object + Send + Sync>],
+    ) -> Option<(Vector, f64)> {
+        if !hit_on(hit, self) {
+            return None;
         }
-        false
+        Some((self.color.as_vector(), 0.0))
+    }
+}
+
+impl Light for Spot {
+    fn move_obj(&mut self, offset: Transform) {
+        self.transform = self.transform + offset
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.transform
+    }
+
+    fn set_transform(&mut self, new: Transform) {
+        self.transform = new
+    }
+
+    fn get_color(&self) -> Color {
+        self.color
+    }
+
+    fn get_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_color(&mut self, new: Color) {
+        self.color = new
+    }
+
+    fn get_strength(&self) -> f64 {
+        self.strength
+    }
+
+    fn set_strength(&mut self, new: f64) {
+        self.strength = new
+    }
+
+    fn get_radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn set_radius(&mut self, new: f64) {
+        self.radius = new
+    }
+
+    fn get_falloff(&self) -> i32 {
+        self.falloff
+    }
+
+    fn set_falloff(&mut self, new: i32) {
+        self.falloff = new
+    }
+
+    fn intersection(&self, ray: Vector, origin: Vector) -> Option<Intersection<'_>> {
+        let diff = origin - self.transform.pos;
+        let result = resolve_quadratic_equation(
+            ray.dot_product(ray),
+            2.0 * (ray.dot_product(diff)),
+            (diff.dot_product(diff)) - self.radius.powi(2),
+        );
+
+        let smallest_result: Option<&f64> = result
+            .iter()
+            .filter(|number| **number > 0.0)
+            .min_by(|a, b| a.partial_cmp(b).unwrap());
+
+        if let Some(smallest) = smallest_result {
+            let point = Vector {
+                x: origin.x + ray.x * smallest,
+                y: origin.y + ray.y * smallest,
+                z: origin.z + ray.z * smallest,
+            };
+            Some(Intersection {
+                normal: point - self.transform.pos,
+                intersection_point: point,
+                object: None,
+                light: Some(self),
+                footprint: 0.0,
+                primitive: None,
+            })
+        } else {
+            None
+        }
+    }
+
+    fn light_transmittance(
+        &self,
+        light_vector: Vector,
+        intersect: &Intersection,
+        normal_vector: Vector,
+        camera: Camera,
+        primitives: &[Box<dyn Object + Send + Sync>],
+        bvh: &Bvh,
+    ) -> Vector {
+        position_transmittance(
+            self.transform.pos,
+            light_vector,
+            intersect,
+            normal_vector,
+            camera,
+            primitives,
+            bvh,
+        )
     }
 
     fn calculate_light(
@@ -341,66 +959,479 @@ impl Light for Directional {
         camera_to_pixel: Vector,
         camera: Camera,
         primitives: &[Box<dyn Object + Send + Sync>],
+        bvh: &Bvh,
     ) -> Vector {
         let normal_vector = intersect.normal.normalize();
-        let mut light_uncovered = 1.0;
+        let light_vector = (self.transform.pos - intersect.intersection_point).normalize();
+        let cone = self.cone_attenuation(light_vector);
+        if cone == 0.0 {
+            return Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            };
+        }
+        let light_uncovered = light_uncovered(
+            self,
+            light_vector,
+            intersect,
+            normal_vector,
+            camera,
+            primitives,
+            bvh,
+        );
+        let distance = intersect.intersection_point.distance(self.transform.pos);
+        let light_falloff = (self.strength / distance.powi(self.falloff)).max(0.0);
 
-        if !camera.smooth_shadow {
-            if self.light_is_intersected(
-                self.transform.pos,
+        surface_response(intersect, light_vector, camera_to_pixel, camera)
+            * self.color.as_vector()
+            * light_falloff
+            * light_uncovered
+            * cone
+    }
+
+    fn sample(
+        &self,
+        origin: Vector,
+        _random: (f64, f64),
+        camera: Camera,
+        _primitives: &[Box<dyn Object + Send + Sync>],
+    ) -> Option<LightSample> {
+        let mut position = self.transform.pos;
+        if camera.smooth_shadow {
+            position = position + Vector::get_random_point_in_sphere(self.radius);
+        }
+        let to_light = position - origin;
+        let cone = self.cone_attenuation(to_light.normalize());
+        if cone == 0.0 {
+            return None;
+        }
+        let distance = origin.distance(self.transform.pos);
+
+        Some(LightSample {
+            direction: to_light.normalize(),
+            distance: to_light.len(),
+            radiance: self.color.as_vector()
+                * (self.strength / distance.powi(self.falloff)).max(0.0)
+                * cone,
+            pdf: 0.0,
+        })
+    }
+
+    fn emission(
+        &self,
+        _origin: Vector,
+        hit: &Intersection,
+        _primitives: &[Box<dyn Object + Send + Sync>],
+    ) -> Option<(Vector, f64)> {
+        if !hit_on(hit, self) {
+            return None;
+        }
+        Some((self.color.as_vector(), 0.0))
+    }
+}
+
+impl Light for Area {
+    fn move_obj(&mut self, offset: Transform) {
+        self.transform = self.transform + offset
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.transform
+    }
+
+    fn set_transform(&mut self, new: Transform) {
+        self.transform = new
+    }
+
+    fn get_color(&self) -> Color {
+        self.color
+    }
+
+    fn get_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_color(&mut self, new: Color) {
+        self.color = new
+    }
+
+    fn get_strength(&self) -> f64 {
+        self.strength
+    }
+
+    fn set_strength(&mut self, new: f64) {
+        self.strength = new
+    }
+
+    fn get_radius(&self) -> f64 {
+        match self.shape {
+            AreaShape::Rectangle { width, height } => {
+                (width * width + height * height).sqrt() / 2.0
+            }
+            AreaShape::Disk { radius } => radius,
+        }
+    }
+
+    fn set_radius(&mut self, new: f64) {
+        if let AreaShape::Disk { ref mut radius } = self.shape {
+            *radius = new
+        }
+    }
+
+    fn get_falloff(&self) -> i32 {
+        2
+    }
+
+    fn set_falloff(&mut self, _new: i32) {}
+
+    fn intersection(&self, ray: Vector, origin: Vector) -> Option<Intersection<'_>> {
+        let (x, y, z) = self.axes();
+        let denom = ray.dot_product(z);
+        if denom == 0.0 {
+            return None;
+        }
+        let progress = (self.transform.pos - origin).dot_product(z) / denom;
+        if progress <= 0.0 {
+            return None;
+        }
+        let point = origin + ray * progress;
+        let local = point - self.transform.pos;
+        let inside = match self.shape {
+            AreaShape::Rectangle { width, height } => {
+                local.dot_product(x).abs() <= width / 2.0
+                    && local.dot_product(y).abs() <= height / 2.0
+            }
+            AreaShape::Disk { radius } => local.len() <= radius,
+        };
+        if !inside {
+            return None;
+        }
+        Some(Intersection {
+            normal: z,
+            intersection_point: point,
+            object: None,
+            light: Some(self),
+            footprint: 0.0,
+            primitive: None,
+        })
+    }
+
+    fn light_transmittance(
+        &self,
+        light_vector: Vector,
+        intersect: &Intersection,
+        normal_vector: Vector,
+        camera: Camera,
+        primitives: &[Box<dyn Object + Send + Sync>],
+        bvh: &Bvh,
+    ) -> Vector {
+        let (_, _, z) = self.axes();
+        let denom = light_vector.dot_product(z);
+        if denom == 0.0 {
+            return Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            };
+        }
+        let progress = (self.transform.pos - intersect.intersection_point).dot_product(z) / denom;
+        position_transmittance(
+            intersect.intersection_point + light_vector * progress,
+            light_vector,
+            intersect,
+            normal_vector,
+            camera,
+            primitives,
+            bvh,
+        )
+    }
+
+    fn calculate_light(
+        &self,
+        intersect: &Intersection,
+        camera_to_pixel: Vector,
+        camera: Camera,
+        primitives: &[Box<dyn Object + Send + Sync>],
+        bvh: &Bvh,
+    ) -> Vector {
+        let normal_vector = intersect.normal.normalize();
+        let (_, _, facing) = self.axes();
+        let samples = stratified_samples(camera.smooth_shadow_step.max(1) as usize);
+        let mut light = Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+
+        for random in samples.iter() {
+            let sample = match self.sample_point(intersect.intersection_point, *random) {
+                Some(sample) => sample,
+                None => continue,
+            };
+            let to_light = sample.point - intersect.intersection_point;
+            let light_vector = to_light.normalize();
+            // only the front face gives off light
+            if light_vector.dot_product(facing) >= 0.0
+                || light_vector.dot_product(normal_vector) <= 0.0
+            {
+                continue;
+            }
+            let transmittance = position_transmittance(
+                sample.point,
+                light_vector,
                 intersect,
                 normal_vector,
                 camera,
                 primitives,
-            ) {
+                bvh,
+            );
+            light = light
+                + surface_response(intersect, light_vector, camera_to_pixel, camera)
+                    * transmittance
+                    * (1.0 / sample.pdf);
+        }
+        light * self.color.as_vector() * self.strength
+            / (std::f64::consts::PI * samples.len() as f64)
+    }
+
+    fn sample(
+        &self,
+        origin: Vector,
+        random: (f64, f64),
+        _camera: Camera,
+        _primitives: &[Box<dyn Object + Send + Sync>],
+    ) -> Option<LightSample> {
+        let (_, _, facing) = self.axes();
+        let sample = self.sample_point(origin, random)?;
+        let to_light = sample.point - origin;
+        if to_light.dot_product(facing) >= 0.0 {
+            return None;
+        }
+
+        Some(LightSample {
+            direction: to_light.normalize(),
+            distance: to_light.len(),
+            radiance: self.color.as_vector() * self.strength,
+            pdf: sample.pdf,
+        })
+    }
+
+    fn emission(
+        &self,
+        origin: Vector,
+        hit: &Intersection,
+        _primitives: &[Box<dyn Object + Send + Sync>],
+    ) -> Option<(Vector, f64)> {
+        if !hit_on(hit, self) {
+            return None;
+        }
+        let (_, _, facing) = self.axes();
+        let to_light = hit.intersection_point - origin;
+        if to_light.dot_product(facing) >= 0.0 {
+            return None;
+        }
+        let pdf = match self.shape {
+            // the density is the same over the whole rectangle
+            AreaShape::Rectangle { width, height } => {
+                self.sample_rectangle(origin, width, height, (0.5, 0.5))?
+                    .pdf
+            }
+            AreaShape::Disk { radius } => {
+                to_light.len2()
+                    / (std::f64::consts::PI
+                        * radius
+                        * radius
+                        * to_light.normalize().dot_product(facing).abs())
+            }
+        };
+
+        Some((self.color.as_vector() * self.strength, pdf))
+    }
+}
+
+impl Light for Emissive {
+    fn move_obj(&mut self, offset: Transform) {
+        self.transform = self.transform + offset
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.transform
+    }
+
+    fn set_transform(&mut self, new: Transform) {
+        self.transform = new
+    }
+
+    fn get_color(&self) -> Color {
+        Color::default()
+    }
+
+    fn get_visible(&self) -> bool {
+        false
+    }
+
+    fn set_color(&mut self, _new: Color) {}
+    fn get_strength(&self) -> f64 {
+        1.0
+    }
+
+    fn set_strength(&mut self, _new: f64) {}
+    fn get_radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn set_radius(&mut self, new: f64) {
+        self.radius = new
+    }
+
+    fn get_falloff(&self) -> i32 {
+        2
+    }
+
+    fn set_falloff(&mut self, _new: i32) {}
+
+    // the primitive is drawn by itself
+    fn intersection(&self, _ray: Vector, _origin: Vector) -> Option<Intersection<'_>> {
+        None
+    }
+
+    fn light_transmittance(
+        &self,
+        light_vector: Vector,
+        intersect: &Intersection,
+        normal_vector: Vector,
+        camera: Camera,
+        primitives: &[Box<dyn Object + Send + Sync>],
+        bvh: &Bvh,
+    ) -> Vector {
+        let origin = intersect.intersection_point + (normal_vector * camera.shadow_bias);
+        let light_distance = match primitives[self.index].intersection(light_vector, origin) {
+            Some(emitter) => (emitter.intersection_point - origin).len(),
+            None => {
                 return Vector {
                     x: 0.0,
                     y: 0.0,
                     z: 0.0,
-                };
-            }
-        } else {
-            let mut light_reached: i16 = 0;
-            for _ in 0..camera.smooth_shadow_step {
-                let inter_to_light = self.transform.pos + Vector::get_random_point_in_sphere(0.0)
-                    - intersect.intersection_point;
-                if !self.light_is_intersected(
-                    inter_to_light.normalize(),
-                    intersect,
-                    normal_vector,
-                    camera,
-                    primitives,
-                ) {
-                    light_reached += 1;
                 }
             }
-            light_uncovered = light_reached as f64 / camera.smooth_shadow_step as f64;
-        }
-        let diffuse = self.transform.pos.dot_product(normal_vector).max(0.0)
-            * camera.diffuse
-            * intersect.object.unwrap().get_texture().diffuse;
-
-        let reflected = self.transform.pos.reflect(normal_vector).normalize();
-        let view = (camera_to_pixel * -1.0).normalize();
-        let specular = camera.specular
-            * intersect.object.unwrap().get_texture().specular
-            * reflected
-                .dot_product(view)
-                .max(0.0)
-                .powf(intersect.object.unwrap().get_texture().shininess);
-        let texture_coordinates = intersect.object.unwrap().surface_position(
-            intersect.intersection_point - intersect.object.unwrap().get_transform().pos,
-        );
-        intersect
-            .object
-            .unwrap()
-            .get_texture()
-            .texture(texture_coordinates.x, texture_coordinates.y)
-            .as_vector()
-            * self.get_color().as_vector()
-            * diffuse
-            * light_uncovered
-            + self.get_color().as_vector() * specular * light_uncovered
+        };
+
+        shadow_transmittance(
+            origin,
+            light_vector,
+            light_distance,
+            Some(self.index),
+            camera,
+            primitives,
+            bvh,
+        )
+    }
+
+    fn calculate_light(
+        &self,
+        intersect: &Intersection,
+        camera_to_pixel: Vector,
+        camera: Camera,
+        primitives: &[Box<dyn Object + Send + Sync>],
+        bvh: &Bvh,
+    ) -> Vector {
+        let normal_vector = intersect.normal.normalize();
+        let origin = intersect.intersection_point + (normal_vector * camera.shadow_bias);
+        let to_center = self.transform.pos - intersect.intersection_point;
+        let samples = camera.smooth_shadow_step.max(1);
+        let mut rng = rand::thread_rng();
+        let mut light = Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+
+        // directions are drawn uniformly in the cone holding the bounding sphere
+        let cos_max = self.cone(intersect.intersection_point);
+        let (tangent, bitangent) = brdf::orthonormal_basis(to_center.normalize());
+        let solid_angle = std::f64::consts::TAU * (1.0 - cos_max);
+        for _ in 0..samples {
+            let cos_theta = 1.0 - rng.gen_range(0.0..1.0) * (1.0 - cos_max);
+            let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+            let phi: f64 = rng.gen_range(0.0..std::f64::consts::TAU);
+            let light_vector = tangent * (sin_theta * phi.cos())
+                + bitangent * (sin_theta * phi.sin())
+                + to_center.normalize() * cos_theta;
+            if light_vector.dot_product(normal_vector) <= 0.0 {
+                continue;
+            }
+            let emitter = match primitives[self.index].intersection(light_vector, origin) {
+                Some(emitter) => emitter,
+                None => continue,
+            };
+            let transmittance = self.light_transmittance(
+                light_vector,
+                intersect,
+                normal_vector,
+                camera,
+                primitives,
+                bvh,
+            );
+            let texture = emitter.object.unwrap().get_texture();
+            light = light
+                + surface_response(intersect, light_vector, camera_to_pixel, camera)
+                    * transmittance
+                    * texture.emission.as_vector()
+                    * texture.emission_strength;
+        }
+        light * (solid_angle / (std::f64::consts::PI * samples as f64))
+    }
+
+    fn sample(
+        &self,
+        origin: Vector,
+        random: (f64, f64),
+        _camera: Camera,
+        primitives: &[Box<dyn Object + Send + Sync>],
+    ) -> Option<LightSample> {
+        let axis = (self.transform.pos - origin).normalize();
+        let cos_max = self.cone(origin);
+        let (tangent, bitangent) = brdf::orthonormal_basis(axis);
+        let cos_theta = 1.0 - random.0 * (1.0 - cos_max);
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = random.1 * std::f64::consts::TAU;
+        let direction = tangent * (sin_theta * phi.cos())
+            + bitangent * (sin_theta * phi.sin())
+            + axis * cos_theta;
+        let emitter = primitives[self.index].intersection(direction, origin)?;
+        let texture = emitter.object.unwrap().get_texture();
+
+        Some(LightSample {
+            direction,
+            distance: (emitter.intersection_point - origin).len(),
+            radiance: texture.emission.as_vector() * texture.emission_strength,
+            pdf: 1.0 / (std::f64::consts::TAU * (1.0 - cos_max)),
+        })
+    }
+
+    fn emission(
+        &self,
+        origin: Vector,
+        hit: &Intersection,
+        _primitives: &[Box<dyn Object + Send + Sync>],
+    ) -> Option<(Vector, f64)> {
+        // meshes, CSG nodes and parents report the part that was hit
+        if hit.primitive != Some(self.index) {
+            return None;
+        }
+        // a primitive may glow in several parts, each only draws its own cone
+        let cos_max = self.cone(origin);
+        let direction = (hit.intersection_point - origin).normalize();
+        if direction.dot_product((self.transform.pos - origin).normalize()) < cos_max - 1e-9 {
+            return None;
+        }
+        let texture = hit.object?.get_texture();
+
+        Some((
+            texture.emission.as_vector() * texture.emission_strength,
+            1.0 / (std::f64::consts::TAU * (1.0 - cos_max)),
+        ))
     }
 }
 
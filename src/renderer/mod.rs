@@ -5,71 +5,172 @@
 // renderer common structures
 //
 
+mod ambient_occlusion;
+pub mod brdf;
+mod bvh;
 mod camera;
+mod caustics;
+pub mod environment;
+mod gltf;
 mod lights;
-mod parsing;
-mod primitives;
+pub mod parsing;
+pub mod path_tracer;
+pub mod photon_map;
+pub mod primitives;
+pub mod sky;
 pub mod types;
 
 use crate::config::Config;
 use crate::renderer::primitives::{Intersection, Object};
 use crate::vector::Vector;
-use camera::Camera;
-use lights::Lights;
+use bvh::Bvh;
+use camera::{Camera, Integrator};
+use environment::Environment;
+use lights::{stratified_samples, surface_response, Emissive, Lights};
 use parsing::Parser;
+use photon_map::PhotonMap;
 use rand::Rng;
+use sky::Sky;
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time;
 
-use self::types::Texture;
+use self::types::{BoundingBox, ImageFilter, Shading, Texture, TexturesTypes, Transform};
 
 pub struct Renderer {
     pub camera: Camera,
     pub primitives: Vec<Box<dyn Object + Send + Sync>>,
     pub lights: Lights,
     pub skybox: Texture,
+    pub environment: Option<Environment>,
+    pub sky: Option<Sky>,
+    pub bvh: Bvh,
+    /// Photons focused by specular objects, gathered to render caustics
+    pub photon_map: Option<PhotonMap>,
 }
 
 struct Recursivity {
     general: i64,
-    transmission: i64,
+    /// Transparent media the ray is currently in, innermost last
+    media: Vec<Medium>,
 }
 
-impl Renderer {
-    fn found_nearest_intersection_fast(&self, origin: Vector, ray: Vector) -> Option<Intersection> {
-        let mut found_intersection: Option<Intersection> = None;
-        let mut smallest_distance: f64 = f64::INFINITY;
+#[derive(Clone, Copy, PartialEq)]
+struct Medium {
+    ior: f64,
+    /// Attenuation coefficients of the red, green and blue channels
+    absorption: Vector,
+}
 
-        for object in self.primitives.iter() {
-            let intersect = object.intersection(ray, origin);
+impl Medium {
+    fn from_texture(texture: &Texture) -> Medium {
+        let coefficient =
+            |channel: f64| -(channel / 255.0).max(1e-6).ln() / texture.absorption_distance;
+
+        Medium {
+            ior: texture.ior,
+            absorption: Vector {
+                x: coefficient(texture.absorption.r),
+                y: coefficient(texture.absorption.g),
+                z: coefficient(texture.absorption.b),
+            },
+        }
+    }
 
-            if let Some(inters) = intersect {
-                let distance_found = (inters.intersection_point - origin).len();
-                if distance_found < smallest_distance {
-                    smallest_distance = distance_found;
-                    found_intersection = Some(inters);
+    /// Share of the light left after going `distance` through the medium,
+    /// following the Beer-Lambert law
+    fn transmittance(&self, distance: f64) -> Vector {
+        Vector {
+            x: (-self.absorption.x * distance).exp(),
+            y: (-self.absorption.y * distance).exp(),
+            z: (-self.absorption.z * distance).exp(),
+        }
+    }
+
+    /// Refractive indices on both sides of a surface of this medium, and
+    /// where the medium sits in the stack when the ray leaves it
+    fn boundary(&self, media: &[Medium], entering: bool) -> (f64, f64, Option<usize>) {
+        let surrounding_ior = |media: &[Medium]| media.last().map_or(1.0, |medium| medium.ior);
+        if entering {
+            return (surrounding_ior(media), self.ior, None);
+        }
+        let index = media.iter().rposition(|other| other == self);
+        let mut outside = media.to_vec();
+        if let Some(index) = index {
+            outside.remove(index);
+        }
+        (self.ior, surrounding_ior(&outside), index)
+    }
+}
+
+impl Renderer {
+    pub fn build_bvh(&mut self) {
+        let boxes: Vec<BoundingBox> = self
+            .primitives
+            .iter()
+            .map(|object| object.bounding_box())
+            .collect();
+        self.bvh = Bvh::new(&boxes);
+    }
+
+    /// Turns every finite part of the primitives with an emissive material,
+    /// such as the glowing materials of a mesh, into a light so that it
+    /// illuminates the rest of the scene
+    pub fn add_emissive_lights(&mut self) {
+        for (index, object) in self.primitives.iter().enumerate() {
+            for bounds in object.emissive_bounds() {
+                if !bounds.is_finite() {
+                    continue;
                 }
+                self.lights.lights.push(Box::new(Emissive {
+                    index,
+                    transform: Transform {
+                        pos: (bounds.min + bounds.max) / 2.0,
+                        ..Transform::default()
+                    },
+                    radius: (bounds.max - bounds.min).len() / 2.0,
+                }));
             }
         }
-        found_intersection
     }
 
-    fn found_nearest_intersection(&self, origin: Vector, ray: Vector) -> Option<Intersection> {
-        let mut found_intersection: Option<Intersection> = None;
-        let mut smallest_distance: f64 = f64::INFINITY;
+    /// World space bounds of every finite object in the scene, infinite
+    /// objects such as planes are left out so the result stays usable
+    pub fn get_scene_bounds(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::default();
 
         for object in self.primitives.iter() {
-            let intersect = object.intersection(ray, origin);
-
-            if let Some(inters) = intersect {
-                let distance_found = (inters.intersection_point - origin).len();
-                if distance_found < smallest_distance {
-                    smallest_distance = distance_found;
-                    found_intersection = Some(inters);
-                }
+            let object_bounds = object.bounding_box();
+            if object_bounds.is_finite() {
+                bounds = bounds.union(object_bounds);
             }
         }
+        bounds
+    }
+
+    /// Closest hit of a ray on the primitives, tagged with the index of the
+    /// primitive it belongs to
+    fn found_nearest_primitive(&self, origin: Vector, ray: Vector) -> Option<Intersection<'_>> {
+        self.bvh.closest_intersection(ray, origin, |index| {
+            let mut intersect = self.primitives[index].intersection(ray, origin)?;
+            intersect.primitive = Some(index);
+            Some(intersect)
+        })
+    }
+
+    fn found_nearest_intersection_fast(&self, origin: Vector, ray: Vector) -> Option<Intersection> {
+        self.found_nearest_primitive(origin, ray)
+    }
+
+    fn found_nearest_intersection(&self, origin: Vector, ray: Vector) -> Option<Intersection> {
+        let mut found_intersection: Option<Intersection> =
+            self.found_nearest_primitive(origin, ray);
+        let mut smallest_distance: f64 = if let Some(inters) = &found_intersection {
+            (inters.intersection_point - origin).len()
+        } else {
+            f64::INFINITY
+        };
+
         for light in self.lights.lights.iter() {
             if light.get_visible() {
                 continue;
@@ -87,7 +188,9 @@ impl Renderer {
         found_intersection
     }
 
-    fn get_ambient(&self, object: &dyn Object, position: Vector) -> Vector {
+    fn get_ambient(&self, intersect: &Intersection, ray: Vector) -> Vector {
+        let object = intersect.object.unwrap();
+        let position = intersect.intersection_point;
         let mut self_color = Vector {
             x: 0.0,
             y: 0.0,
@@ -100,16 +203,118 @@ impl Renderer {
             self_color = self_color
                 + object
                     .get_texture()
-                    .texture(texture_coordinates.x, texture_coordinates.y)
+                    .sample(
+                        texture_coordinates.x,
+                        texture_coordinates.y,
+                        intersect.footprint,
+                    )
                     .as_vector()
                     * object.get_texture().ambient
                     * ambient.color.as_vector()
                     * ambient.strength
                     * self.camera.ambient;
         }
+        if self.camera.ambient_occlusion && !self.lights.ambient.is_empty() {
+            self_color = self_color * self.ambient_occlusion(intersect, ray);
+        }
         self_color
     }
 
+    /// Size in texture space of the pixel seen at a hit, only mip-mapped images need it
+    fn texture_footprint(&self, intersect: &Intersection, origin: Vector) -> f64 {
+        let object = match intersect.object {
+            Some(object) => object,
+            None => return 0.0,
+        };
+        if object.get_texture().sampler.filter != ImageFilter::Trilinear
+            && object.get_normal_map().sampler.filter != ImageFilter::Trilinear
+        {
+            return 0.0;
+        }
+        let position = intersect.intersection_point - object.get_transform().pos;
+        let normal = intersect.normal.normalize();
+        let tangent = object.tangent(position).normalize();
+        let step = 1e-3;
+        let start = object.surface_position(position);
+        // texture distance covered by a unit step on the surface, ignoring wrap arounds
+        let rate = |direction: Vector| {
+            let end = object.surface_position(position + direction * step);
+            let (delta_u, delta_v) = (end.x - start.x, end.y - start.y);
+            (delta_u - delta_u.round()).hypot(delta_v - delta_v.round()) / step
+        };
+        let texture_per_unit = rate(tangent).max(rate(normal.cross_product(tangent)));
+        let view = intersect.intersection_point - origin;
+        // grazing rays see the pixel stretched along the surface
+        let slant = view.normalize().dot_product(normal).abs().max(0.05);
+        let footprint = view.len() / self.camera.lens.distance / slant * texture_per_unit;
+
+        if footprint.is_finite() {
+            footprint
+        } else {
+            0.0
+        }
+    }
+
+    /// Bends the normal of a hit with the normal map of its object, either given as
+    /// tangent space normals or as a height map when it has a bump strength
+    fn apply_normal_map(&self, intersect: &mut Intersection) {
+        let object = match intersect.object {
+            Some(object) => object,
+            None => return,
+        };
+        let normal_map = object.get_normal_map();
+
+        // a uniform map can't carry any relief
+        if let TexturesTypes::Color = TexturesTypes::from_u64(normal_map.texture_type) {
+            return;
+        }
+        let normal = intersect.normal.normalize();
+        let position = intersect.intersection_point - object.get_transform().pos;
+        let mut tangent = object.tangent(position);
+        tangent = tangent - normal * tangent.dot_product(normal);
+        if tangent.len() < 1e-9 {
+            tangent = normal.cross_product(if normal.x.abs() < 0.9 {
+                Vector {
+                    x: 1.0,
+                    y: 0.0,
+                    z: 0.0,
+                }
+            } else {
+                Vector {
+                    x: 0.0,
+                    y: 1.0,
+                    z: 0.0,
+                }
+            });
+        }
+        let tangent = tangent.normalize();
+        let mut bitangent = normal.cross_product(tangent);
+        let texture_coordinates = object.surface_position(position);
+        let (u, v) = (texture_coordinates.x, texture_coordinates.y);
+        let step = 1e-3;
+        // mirrored mappings, like imported meshes, grow v the other way around
+        let ahead = object.surface_position(position + bitangent * step).y - v;
+        if ahead - ahead.round() < 0.0 {
+            bitangent = bitangent * -1.0;
+        }
+
+        if normal_map.bump != 0.0 {
+            // slopes of the height field along both texture directions
+            let height = normal_map.height(u, v);
+            let slope_u = (normal_map.height(u + step, v) - height) / step;
+            let slope_v = (normal_map.height(u, v + step) - height) / step;
+
+            intersect.normal =
+                (normal - (tangent * slope_u + bitangent * slope_v) * normal_map.bump).normalize();
+        } else {
+            let sample = normal_map.sample(u, v, intersect.footprint).as_vector() * 2.0 - 1.0;
+
+            // images run against u, so their red channel leans away from the tangent
+            intersect.normal =
+                (tangent * -sample.x + bitangent * sample.y + normal * sample.z).normalize();
+        }
+    }
+
     fn combine_pixel(&self, samples: &[Vector]) -> Vector {
         let mut result: Vector = samples[0];
 
@@ -132,6 +337,60 @@ impl Renderer {
         }
     }
 
+    /// Color seen by rays leaving the scene
+    fn background(&self, ray: Vector) -> Vector {
+        if let Some(sky) = &self.sky {
+            return sky.radiance(ray);
+        }
+        if let Some(environment) = &self.environment {
+            return environment.radiance(ray);
+        }
+        let skybox_pos = self.skybox_position(ray);
+        self.skybox.texture(skybox_pos.x, skybox_pos.y).as_vector()
+    }
+
+    /// Image based lighting, directions are drawn where the environment is
+    /// the brightest
+    fn environment_light(&self, intersect: &Intersection, ray: Vector) -> Vector {
+        let mut light = Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let environment = match &self.environment {
+            Some(environment) => environment,
+            None => return light,
+        };
+        let normal = intersect.normal.normalize();
+        let origin = intersect.intersection_point + normal * self.camera.shadow_bias;
+        let samples = stratified_samples(environment.samples.max(1));
+
+        for random in samples.iter() {
+            let (direction, pdf) = match environment.sample(*random) {
+                Some(sample) => sample,
+                None => continue,
+            };
+            if direction.dot_product(normal) <= 0.0 || pdf <= 0.0 {
+                continue;
+            }
+            if self
+                .bvh
+                .any_intersection(direction, origin, f64::INFINITY, |index| {
+                    self.primitives[index]
+                        .intersection(direction, origin)
+                        .is_some()
+                })
+            {
+                continue;
+            }
+            light = light
+                + surface_response(intersect, direction, ray, self.camera)
+                    * environment.radiance(direction)
+                    * (1.0 / pdf);
+        }
+        light / (std::f64::consts::PI * samples.len() as f64)
+    }
+
     fn get_color_from_ray_fast(&self, origin: Vector, ray: Vector) -> Vector {
         let maybe_intersect = self.found_nearest_intersection_fast(origin, ray);
 
@@ -165,10 +424,7 @@ impl Renderer {
                     .as_vector()
                     * diffuse
         } else {
-            let skybox_position = self.skybox_position(ray);
-            self.skybox
-                .texture(skybox_position.x, skybox_position.y)
-                .as_vector()
+            self.background(ray)
         }
     }
 
@@ -186,63 +442,124 @@ impl Renderer {
         Some(final_vect)
     }
 
+    /// Mirror reflection of a ray pulled toward a random direction as the
+    /// surface gets rougher
+    fn glossy_reflection(&self, normal: Vector, incident: Vector, roughness: f64) -> Vector {
+        let mut rng = rand::thread_rng();
+        // random vector used for the roughness
+        let random_a: f64 = rng.gen_range(0.0..std::f64::consts::TAU);
+        let random_b: f64 = rng.gen_range(0.0..std::f64::consts::TAU);
+        let random_vect = Vector {
+            x: random_a.cos() * random_b.cos(),
+            y: random_a.sin() * random_b.cos(),
+            z: random_b.sin(),
+        };
+        let mut reflection_ray = (incident.normalize()
+            - (normal.normalize() * 2.0 * normal.normalize().dot_product(incident.normalize())))
+        .normalize();
+        if roughness != 0.0 {
+            reflection_ray.lerp(&random_vect, roughness);
+        }
+        reflection_ray
+    }
+
+    /// Splits a ray hitting a transparent surface between reflection and
+    /// refraction with the Fresnel equations, the media the ray is in are
+    /// stacked so that nested objects refract against their surroundings
     fn transmission(
         &self,
         intersect: &Intersection,
         incident_ray: Vector,
         recursivity: &mut Recursivity,
     ) -> Vector {
-        let normal = intersect.normal.normalize();
-        let other_ior = 1.0;
-        let object_ior = intersect.object.unwrap().get_texture().ior;
-
-        let maybe_new_ray = if recursivity.transmission <= 1 {
-            self.refract(
-                normal.normalize() * -1.0,
-                incident_ray.normalize(),
-                object_ior,
-                other_ior,
-            )
-        } else {
-            self.refract(
-                normal.normalize(),
-                incident_ray.normalize(),
-                other_ior,
-                object_ior,
-            )
+        let texture = intersect.object.unwrap().get_texture();
+        let incident = incident_ray.normalize();
+        let mut normal = intersect.normal.normalize();
+        let entering = normal.dot_product(incident) < 0.0;
+        if !entering {
+            normal = normal * -1.0;
+        }
+        let medium = Medium::from_texture(&texture);
+        let (ior1, ior2, index) = medium.boundary(&recursivity.media, entering);
+        let cos_i = -normal.dot_product(incident);
+        let reflectance = brdf::dielectric_fresnel(cos_i, ior1, ior2);
+        let mut color = Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
         };
-        if let Some(new_ray) = maybe_new_ray {
-            let maybe_intersect = self.found_nearest_intersection(
-                intersect.intersection_point + new_ray * self.camera.shadow_bias,
-                new_ray,
-            );
-            if let Some(new_intersect) = maybe_intersect {
-                if recursivity.transmission == 2
-                    && new_intersect.object.unwrap().get_texture().transmission > 0.0
-                {
-                    recursivity.transmission = 1;
-                    return self.transmission(&new_intersect, new_ray, recursivity);
-                } else {
-                    recursivity.general -= 1;
-                    return self.get_color_from_ray(
-                        intersect.intersection_point + new_ray * self.camera.shadow_bias,
-                        new_ray,
-                        recursivity,
-                    );
-                }
+
+        if let Some(refracted) = self.refract(normal, incident, ior1, ior2) {
+            if entering {
+                recursivity.media.push(medium);
+            } else if let Some(index) = index {
+                recursivity.media.remove(index);
+            }
+            let origin = intersect.intersection_point - normal * self.camera.shadow_bias;
+            recursivity.general -= 1;
+            color = self.get_color_from_ray(origin, refracted, recursivity) * (1.0 - reflectance);
+            if entering {
+                recursivity.media.pop();
+            } else if let Some(index) = index {
+                recursivity.media.insert(index, medium);
             }
-        } else {
-            return Vector {
-                x: 0.0,
-                y: 0.0,
-                z: 0.0,
-            };
         }
-        Vector {
+        if reflectance > 0.0 && recursivity.general > 1 {
+            let reflected = incident - normal * 2.0 * incident.dot_product(normal);
+            let origin = intersect.intersection_point + normal * self.camera.shadow_bias;
+            recursivity.general -= 1;
+            color = color + self.get_color_from_ray(origin, reflected, recursivity) * reflectance;
+        }
+        color
+    }
+
+    /// Reflections of a microfacet material, importance sampled along the
+    /// GGX distribution and weighted by Smith masking and Schlick Fresnel
+    fn microfacet_reflection(
+        &self,
+        intersect: &Intersection,
+        ray: Vector,
+        recursivity: &mut Recursivity,
+    ) -> Vector {
+        let object = intersect.object.unwrap();
+        let texture = object.get_texture();
+        let view = (ray * -1.0).normalize();
+        let mut normal = intersect.normal.normalize();
+        if normal.dot_product(view) < 0.0 {
+            normal = normal * -1.0;
+        }
+        let texture_coordinates =
+            object.surface_position(intersect.intersection_point - object.get_transform().pos);
+        let albedo = texture
+            .sample(
+                texture_coordinates.x,
+                texture_coordinates.y,
+                intersect.footprint,
+            )
+            .as_vector();
+        let f0 = brdf::base_reflectance(albedo, texture.metalness);
+        let alpha = brdf::alpha(texture.roughness);
+        let surface_point = intersect.intersection_point + normal * self.camera.shadow_bias;
+        let samples_nbr = (1.0 + self.camera.reflection_samples as f64 * texture.roughness)
+            .powf(texture.sampling_ponderation) as i32;
+        let mut color = Vector {
             x: 0.0,
             y: 0.0,
             z: 0.0,
+        };
+
+        for _ in 0..samples_nbr {
+            let half = brdf::sample_ggx(normal, alpha);
+            let reflection_ray = view.reflect(half);
+            let weight = brdf::reflection_weight(f0, alpha, normal, view, reflection_ray, half);
+            if weight.len2() == 0.0 {
+                continue;
+            }
+            recursivity.general -= 1;
+            color = color
+                + self.get_color_from_ray(surface_point, reflection_ray, recursivity) * weight;
         }
+        color * (1.0 / samples_nbr.max(1) as f64)
     }
 
     fn get_color_from_ray(
@@ -261,96 +578,134 @@ impl Renderer {
         let maybe_intersect = self.found_nearest_intersection(origin, ray);
 
         if let Some(intersect) = maybe_intersect {
-            if self.camera.display_normals {
-                return intersect.normal.normalize() * 0.5 + 0.5;
-            } else if self.camera.display_location {
-                return intersect.intersection_point * 0.5 + 0.5;
-            } else if self.camera.display_dot_product {
-                return Vector {
-                    x: intersect.normal.normalize().dot_product(ray.normalize()) * 0.5 + 0.5,
-                    y: intersect.normal.normalize().dot_product(ray.normalize()) * 0.5 + 0.5,
-                    z: intersect.normal.normalize().dot_product(ray.normalize()) * 0.5 + 0.5,
-                };
-            }
+            // light fades through the medium the ray travels in
+            let transmittance = match recursivity.media.last() {
+                Some(medium) => medium.transmittance((intersect.intersection_point - origin).len()),
+                None => Vector {
+                    x: 1.0,
+                    y: 1.0,
+                    z: 1.0,
+                },
+            };
+            self.get_color_from_intersection(intersect, origin, ray, recursivity) * transmittance
+        } else {
+            self.background(ray)
+        }
+    }
 
-            // case of direct intersection with light object
-            if let Some(light_touched) = intersect.light {
-                return light_touched.get_color().as_vector();
-            }
-            let mut self_color =
-                self.get_ambient(intersect.object.unwrap(), intersect.intersection_point);
+    /// Color seen along `ray` where it hits a surface, leaving out what the
+    /// medium it travels in absorbs on the way
+    fn get_color_from_intersection(
+        &self,
+        mut intersect: Intersection,
+        origin: Vector,
+        ray: Vector,
+        recursivity: &mut Recursivity,
+    ) -> Vector {
+        intersect.footprint = self.texture_footprint(&intersect, origin);
+        self.apply_normal_map(&mut intersect);
+        if self.camera.display_normals {
+            return intersect.normal.normalize() * 0.5 + 0.5;
+        } else if self.camera.display_location {
+            return intersect.intersection_point * 0.5 + 0.5;
+        } else if self.camera.display_dot_product {
+            return Vector {
+                x: intersect.normal.normalize().dot_product(ray.normalize()) * 0.5 + 0.5,
+                y: intersect.normal.normalize().dot_product(ray.normalize()) * 0.5 + 0.5,
+                z: intersect.normal.normalize().dot_product(ray.normalize()) * 0.5 + 0.5,
+            };
+        } else if self.camera.display_ambient_occlusion {
+            let occlusion = if intersect.object.is_some() {
+                self.ambient_occlusion(&intersect, ray)
+            } else {
+                1.0
+            };
+            return Vector {
+                x: occlusion,
+                y: occlusion,
+                z: occlusion,
+            };
+        }
 
-            // calculation of lighting
-            for light in self.lights.lights.iter() {
-                self_color = self_color
-                    + light.calculate_light(&intersect, ray, self.camera, &self.primitives);
-            }
+        // case of direct intersection with light object
+        if let Some(light_touched) = intersect.light {
+            return light_touched.get_color().as_vector();
+        }
+        let pbr = intersect.object.unwrap().get_texture().shading == Shading::Pbr;
+        let mut self_color = self.get_ambient(&intersect, ray);
+        if pbr {
+            // the microfacet model already weights direct light by metalness
+            self_color = self_color * (1.0 - intersect.object.unwrap().get_texture().metalness);
+        }
 
-            let surface_point =
-                intersect.intersection_point + intersect.normal * self.camera.shadow_bias;
+        // calculation of lighting
+        self_color = self_color + self.environment_light(&intersect, ray);
+        for light in self.lights.lights.iter() {
+            self_color = self_color
+                + light.calculate_light(
+                    &intersect,
+                    ray,
+                    self.camera,
+                    &self.primitives,
+                    &self.bvh,
+                );
+        }
+
+        let surface_point =
+            intersect.intersection_point + intersect.normal * self.camera.shadow_bias;
 
+        if !pbr {
             self_color = self_color * (1.0 - intersect.object.unwrap().get_texture().metalness);
-            if recursivity.general <= 1 {
-                return self_color;
-            }
+        }
+        self_color = self_color + self.caustic_light(&intersect, ray);
+        let texture = intersect.object.unwrap().get_texture();
+        let emission = texture.emission.as_vector() * texture.emission_strength;
+        if recursivity.general <= 1 {
+            return self_color + emission;
+        }
+        let transmission = intersect.object.unwrap().get_texture().transmission;
+        if transmission != 0.0 {
+            self_color = self_color * (1.0 - transmission)
+                + self.transmission(&intersect, ray, recursivity) * transmission;
+        } else if pbr {
+            self_color = self_color + self.microfacet_reflection(&intersect, ray, recursivity);
+        } else {
             let samples_nbr = (1.0
                 + self.camera.reflection_samples as f64
                     * intersect.object.unwrap().get_texture().roughness)
                 .powf(intersect.object.unwrap().get_texture().sampling_ponderation);
             for _ in 0..samples_nbr as i32 {
-                let mut rng = rand::thread_rng();
-                // random vector used for the roughness
-                let random_a: f64 = rng.gen_range(0.0..std::f64::consts::TAU);
-                let random_b: f64 = rng.gen_range(0.0..std::f64::consts::TAU);
-                let random_vect = Vector {
-                    x: random_a.cos() * random_b.cos(),
-                    y: random_a.sin() * random_b.cos(),
-                    z: random_b.sin(),
-                };
-                let mut reflection_ray = (ray.normalize()
-                    - (intersect.normal.normalize()
-                        * 2.0
-                        * intersect.normal.normalize().dot_product(ray.normalize())))
-                .normalize();
-                if intersect.object.unwrap().get_texture().roughness != 0.0 {
-                    reflection_ray.lerp(
-                        &random_vect,
-                        intersect.object.unwrap().get_texture().roughness,
-                    );
-                }
-                let metalness = intersect.object.unwrap().get_texture().metalness;
-                let new_color;
-                if intersect.object.unwrap().get_texture().transmission == 0.0 {
-                    recursivity.general -= 1;
-                    new_color = self.get_color_from_ray(surface_point, reflection_ray, recursivity);
-                    self_color = self_color
-                        + ((new_color
-                            * (1.0 - metalness)
-                            * intersect.object.unwrap().get_texture().specular)
-                            + (new_color
-                                * intersect.object.unwrap().get_texture().color.as_vector()
-                                * metalness))
-                            * (1.0 / samples_nbr);
-                } else {
-                    recursivity.transmission = 2;
-                    self_color = self.transmission(&intersect, ray, recursivity);
-                }
-            }
-            if intersect.object.unwrap().get_texture().alpha != 1.0 {
-                recursivity.general -= 1;
-                let new_color = self.get_color_from_ray(
-                    intersect.intersection_point + ray * self.camera.shadow_bias,
+                let reflection_ray = self.glossy_reflection(
+                    intersect.normal,
                     ray,
-                    recursivity,
+                    intersect.object.unwrap().get_texture().roughness,
                 );
-                return self_color * intersect.object.unwrap().get_texture().alpha
-                    + new_color * (1.0 - intersect.object.unwrap().get_texture().alpha);
+                let metalness = intersect.object.unwrap().get_texture().metalness;
+                recursivity.general -= 1;
+                let new_color =
+                    self.get_color_from_ray(surface_point, reflection_ray, recursivity);
+                self_color = self_color
+                    + ((new_color
+                        * (1.0 - metalness)
+                        * intersect.object.unwrap().get_texture().specular)
+                        + (new_color
+                            * intersect.object.unwrap().get_texture().color.as_vector()
+                            * metalness))
+                        * (1.0 / samples_nbr);
             }
-            self_color
-        } else {
-            let skybox_pos = self.skybox_position(ray);
-            self.skybox.texture(skybox_pos.x, skybox_pos.y).as_vector()
         }
+        self_color = self_color + emission;
+        if intersect.object.unwrap().get_texture().alpha != 1.0 {
+            recursivity.general -= 1;
+            let new_color = self.get_color_from_ray(
+                intersect.intersection_point + ray * self.camera.shadow_bias,
+                ray,
+                recursivity,
+            );
+            return self_color * intersect.object.unwrap().get_texture().alpha
+                + new_color * (1.0 - intersect.object.unwrap().get_texture().alpha);
+        }
+        self_color
     }
 
     fn check_pixels_proximity(&self, samples: &Vec<Vector>) -> bool {
@@ -375,6 +730,26 @@ impl Renderer {
                 self.camera.get_pixel_vectors(x, y, 1)[0],
             );
         }
+        let debug_view = self.camera.display_normals
+            || self.camera.display_location
+            || self.camera.display_dot_product
+            || self.camera.display_ambient_occlusion;
+        if self.camera.integrator == Integrator::Path && !debug_view {
+            let samples = self.camera.super_sampling.max(1);
+            let mut color = Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            };
+            for _ in 0..samples {
+                color = color
+                    + self.trace_path(
+                        self.camera.transform.pos,
+                        self.camera.get_random_pixel_vector(x, y),
+                    );
+            }
+            return color / samples as f64;
+        }
 
         let mut samples: Vec<Vector> = Vec::new();
         let mut camera_to_pixel_vector =
@@ -383,7 +758,7 @@ impl Renderer {
         for i in 0..camera_to_pixel_vector.len() {
             let mut recursion = Recursivity {
                 general: self.camera.recursivity,
-                transmission: 10,
+                media: Vec::new(),
             };
             samples.push(self.get_color_from_ray(
                 self.camera.transform.pos,
@@ -579,6 +954,9 @@ impl Renderer {
     pub fn get_renderer_from_file(config: &Config) -> Option<Renderer> {
         let mut _result: Option<Renderer> = None;
         let parser = Parser {};
+        if gltf::is_gltf_file(&config.config_file) {
+            return parser.get_renderer_from_gltf(&config.config_file, config.height, config.width);
+        }
         if parser.get_json(&config.config_file).is_some() {
             _result = Some(parser.get_renderer_from_json(
                 &parser.get_json(&config.config_file).unwrap(),
@@ -5,20 +5,32 @@
 // json parsing
 //
 
-use nannou::image::io::Reader;
 use std::fs;
 
-use super::camera::{Camera, Lens};
-use super::lights::{Ambient, Directional, Light, Lights, Point};
-use super::primitives::mesh::Mesh;
+use super::bvh::Bvh;
+use super::camera::{Camera, Integrator, Lens};
+use super::environment::Environment;
+use super::gltf::{is_gltf_file, GltfScene};
+use super::lights::{Ambient, Area, AreaShape, Directional, Light, Lights, Point, Spot};
+use super::primitives::mesh::{Displacement, Mesh, MAX_SUBDIVISIONS};
 use super::primitives::{
-    cone::Cone, cylinder::Cylinder, parent::Parent, plane::Plane, sphere::Sphere,
-    triangle::Triangle, Object,
+    cone::Cone,
+    csg::{Csg, CsgOperation},
+    cuboid::Cuboid,
+    cylinder::Cylinder,
+    parent::Parent,
+    plane::Plane,
+    sdf::{Sdf, SdfNode},
+    sphere::Sphere,
+    torus::Torus,
+    triangle::Triangle,
+    Object,
 };
-use super::types::{Color, Image, Texture, Transform};
+use super::sky::Sky;
+use super::types::{Color, Image, ImageFilter, ImageWrap, Sampler, Shading, Texture, Transform};
 use super::Renderer;
 use crate::vector;
-use serde_json::Value;
+use serde_json::{Map, Value};
 use vector::Vector;
 
 pub struct Parser {}
@@ -83,6 +95,18 @@ impl Parser {
             display_normals: json["display_normals"].as_bool().unwrap_or(false),
             display_location: json["display_location"].as_bool().unwrap_or(false),
             display_dot_product: json["display_dot_product"].as_bool().unwrap_or(false),
+            display_ambient_occlusion: json["display_ambient_occlusion"].as_bool().unwrap_or(false),
+            ambient_occlusion: json["ambient_occlusion"].as_bool().unwrap_or(false),
+            ambient_occlusion_samples: json["ambient_occlusion_samples"].as_u64().unwrap_or(16),
+            ambient_occlusion_distance: json["ambient_occlusion_distance"].as_f64().unwrap_or(1.0),
+            ambient_occlusion_pass: json["ambient_occlusion_pass"].as_bool().unwrap_or(false),
+            integrator: match json["integrator"].as_str() {
+                Some("path") => Integrator::Path,
+                _ => Integrator::Whitted,
+            },
+            caustic_photons: json["caustic_photons"].as_u64().unwrap_or(0),
+            caustic_radius: json["caustic_radius"].as_f64().unwrap_or(0.1),
+            caustic_neighbours: json["caustic_neighbours"].as_u64().unwrap_or(64),
         };
         camera.calculate_lens_distance();
         camera.calculate_lens_size();
@@ -103,22 +127,51 @@ impl Parser {
     }
 
     pub fn get_image_from_json(&self, json: &Value) -> Image {
-        let filename = json["image"]
-            .as_str()
-            .unwrap_or("assets/missing_texture.ppm")
-            .to_string();
-        let mut reader = Reader::open(&filename)
-            .unwrap_or(
-                Reader::open("assets/missing_texture.ppm").expect("missing missing texture texture\n"),
-            )
-            .decode()
-            .expect("file invalid\n");
-        let data = reader.as_mut_rgb8().expect("file invalid");
+        Image::from_file(
+            json["image"]
+                .as_str()
+                .unwrap_or("assets/missing_texture.ppm"),
+        )
+    }
+
+    pub fn get_sampler_from_json(&self, json: &Value) -> Sampler {
+        let sampler = &json["sampler"];
 
-        Image {
-            height: data.height() as i64,
-            width: data.width() as i64,
-            file: filename,
+        Sampler {
+            filter: match sampler["filter"].as_str() {
+                Some("nearest") => ImageFilter::Nearest,
+                Some("trilinear") => ImageFilter::Trilinear,
+                _ => ImageFilter::Bilinear,
+            },
+            wrap: match sampler["wrap"].as_str() {
+                Some("clamp") => ImageWrap::Clamp,
+                Some("mirror") => ImageWrap::Mirror,
+                _ => ImageWrap::Repeat,
+            },
+            offset: if sampler["offset"].is_object() {
+                self.get_vector_from_json(&sampler["offset"])
+            } else {
+                Vector {
+                    x: 0.0,
+                    y: 0.0,
+                    z: 0.0,
+                }
+            },
+            rotation: sampler["rotation"].as_f64().unwrap_or(0.0),
+            // older scenes tile their images with mod1 and mod2
+            scale: if sampler["scale"].is_object() {
+                Vector {
+                    x: sampler["scale"]["x"].as_f64().unwrap_or(1.0),
+                    y: sampler["scale"]["y"].as_f64().unwrap_or(1.0),
+                    z: 0.0,
+                }
+            } else {
+                Vector {
+                    x: json["mod1"].as_f64().unwrap_or(2.0),
+                    y: json["mod2"].as_f64().unwrap_or(2.0),
+                    z: 0.0,
+                }
+            },
         }
     }
 
@@ -152,6 +205,13 @@ impl Parser {
             alpha: 0.0,
             transmission: 0.0,
             ior: 0.0,
+            absorption: Color::default(),
+            absorption_distance: 1.0,
+            emission: Color::default(),
+            emission_strength: 0.0,
+            sampler: self.get_sampler_from_json(json),
+            bump: json["bump"].as_f64().unwrap_or(0.0),
+            shading: Shading::Phong,
         }
     }
 
@@ -183,8 +243,26 @@ impl Parser {
             roughness: json["roughness"].as_f64().unwrap_or(0.25),
             transmission: json["transmission"].as_f64().unwrap_or(0.0),
             ior: json["ior"].as_f64().unwrap_or(1.45),
+            absorption: if json["absorption"].is_object() {
+                self.get_color_from_json(&json["absorption"])
+            } else {
+                Color::default()
+            },
+            absorption_distance: json["absorption_distance"].as_f64().unwrap_or(1.0),
+            emission: if json["emission"].is_object() {
+                self.get_color_from_json(&json["emission"])
+            } else {
+                Color::default()
+            },
+            emission_strength: json["emission_strength"].as_f64().unwrap_or(0.0),
             sampling_ponderation: json["sampling_ponderation"].as_f64().unwrap_or(1.0),
             alpha: json["alpha"].as_f64().unwrap_or(1.0),
+            sampler: self.get_sampler_from_json(json),
+            bump: 0.0,
+            shading: match json["shading"].as_str() {
+                Some("pbr") => Shading::Pbr,
+                _ => Shading::Phong,
+            },
         }
     }
 
@@ -370,6 +448,98 @@ impl Parser {
         Box::new(cone)
     }
 
+    pub fn get_torus_from_json(&self, json: &Value) -> Box<Torus> {
+        let mut torus = Torus {
+            name: json["name"].as_str().unwrap_or("Nan").to_string(),
+            obj_type: "torus".to_string(),
+            transform: if json["transform"].is_object() {
+                self.get_transform_from_json(&json["transform"])
+            } else {
+                Transform::default()
+            },
+            texture: if json["texture"].is_object() {
+                self.get_texture_from_json(&json["texture"])
+            } else {
+                Texture::default()
+            },
+            normal_map: if json["normal_map"].is_object() {
+                self.get_normal_map_from_json(&json["normal_map"])
+            } else {
+                Texture::normal_map_default()
+            },
+            major_radius: json["major_radius"].as_f64().unwrap_or(2.0),
+            minor_radius: json["minor_radius"].as_f64().unwrap_or(0.5),
+            axis: Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            major_radius_applied: 0.0,
+            minor_radius_applied: 0.0,
+            children: if json["children"].is_array() {
+                self.get_objects_from_json(&json["children"])
+            } else {
+                Vec::new()
+            },
+        };
+        torus.apply_transform();
+        for i in 0..torus.children.len() {
+            torus.children[i].move_obj(torus.transform);
+        }
+        Box::new(torus)
+    }
+
+    pub fn get_cuboid_from_json(&self, json: &Value) -> Box<Cuboid> {
+        let mut cuboid = Cuboid {
+            name: json["name"].as_str().unwrap_or("Nan").to_string(),
+            obj_type: "box".to_string(),
+            transform: if json["transform"].is_object() {
+                self.get_transform_from_json(&json["transform"])
+            } else {
+                Transform::default()
+            },
+            texture: if json["texture"].is_object() {
+                self.get_texture_from_json(&json["texture"])
+            } else {
+                Texture::default()
+            },
+            normal_map: if json["normal_map"].is_object() {
+                self.get_normal_map_from_json(&json["normal_map"])
+            } else {
+                Texture::normal_map_default()
+            },
+            size: if json["size"].is_object() {
+                Vector {
+                    x: json["size"]["x"].as_f64().unwrap_or(1.0),
+                    y: json["size"]["y"].as_f64().unwrap_or(1.0),
+                    z: json["size"]["z"].as_f64().unwrap_or(1.0),
+                }
+            } else {
+                Vector {
+                    x: 1.0,
+                    y: 1.0,
+                    z: 1.0,
+                }
+            },
+            axes: [Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            }; 3],
+            half_size_applied: [0.0; 3],
+            children: if json["children"].is_array() {
+                self.get_objects_from_json(&json["children"])
+            } else {
+                Vec::new()
+            },
+        };
+        cuboid.apply_transform();
+        for i in 0..cuboid.children.len() {
+            cuboid.children[i].move_obj(cuboid.transform);
+        }
+        Box::new(cuboid)
+    }
+
     pub fn get_triangle_from_json(&self, json: &Value) -> Box<Triangle> {
         let mut triangle = Triangle {
             name: json["name"].as_str().unwrap_or("Nan").to_string(),
@@ -436,6 +606,13 @@ impl Parser {
                 y: 0.0,
                 z: 0.0,
             },
+            vertex_normals: None,
+            vertex_uvs: None,
+            vertex_normals_applied: [Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            }; 3],
             children: if json["children"].is_array() {
                 self.get_objects_from_json(&json["children"])
             } else {
@@ -449,6 +626,43 @@ impl Parser {
         Box::new(triangle)
     }
 
+    pub fn get_displacement_from_json(&self, json: &Value) -> Displacement {
+        let subdivisions = json["subdivisions"].as_u64().unwrap_or(3);
+
+        if subdivisions > MAX_SUBDIVISIONS {
+            println!("Displacement subdivisions capped to {} !", MAX_SUBDIVISIONS);
+        }
+        Displacement {
+            texture: if json["texture"].is_object() {
+                self.get_texture_from_json(&json["texture"])
+            } else {
+                Texture::default()
+            },
+            scale: json["scale"].as_f64().unwrap_or(0.1),
+            subdivisions: subdivisions.min(MAX_SUBDIVISIONS),
+        }
+    }
+
+    /// A displaced triangle needs to be tessellated, so it turns into a mesh
+    pub fn get_displaced_triangle_from_json(&self, json: &Value) -> Box<Mesh> {
+        let mut triangle = self.get_triangle_from_json(json);
+        let mut mesh = Mesh {
+            name: triangle.name.clone(),
+            obj_type: "mesh".to_string(),
+            transform: triangle.transform,
+            texture: triangle.texture.clone(),
+            normal_map: triangle.normal_map.clone(),
+            triangles: Vec::new(),
+            bvh: Bvh::default(),
+            children: std::mem::take(&mut triangle.children),
+        };
+
+        mesh.triangles.push(*triangle);
+        mesh.displace(&self.get_displacement_from_json(&json["displacement"]));
+        mesh.apply_transform();
+        Box::new(mesh)
+    }
+
     pub fn get_mesh_from_json(&self, json: &Value) -> Box<Mesh> {
         let mut mesh = Mesh {
             name: json["name"].as_str().unwrap_or("Nan").to_string(),
@@ -469,6 +683,7 @@ impl Parser {
                 Texture::normal_map_default()
             },
             triangles: Vec::new(),
+            bvh: Bvh::default(),
             children: if json["children"].is_array() {
                 self.get_objects_from_json(&json["children"])
             } else {
@@ -479,6 +694,9 @@ impl Parser {
             let filename = json["file"].as_str().unwrap();
             mesh.parse_obj(filename);
         }
+        if json["displacement"].is_object() {
+            mesh.displace(&self.get_displacement_from_json(&json["displacement"]));
+        }
         mesh.apply_transform();
         for i in 0..mesh.children.len() {
             mesh.children[i].move_obj(mesh.transform);
@@ -507,6 +725,144 @@ impl Parser {
         parent
     }
 
+    pub fn get_csg_from_json(&self, json: &Value) -> Option<Box<Csg>> {
+        for operand in [&json["left"], &json["right"]] {
+            if operand["type"].as_str() == Some("triangle") {
+                println!("A triangle has no inside, it can't be a CSG operand !");
+                return None;
+            }
+        }
+        let mut csg = Box::new(Csg {
+            name: json["name"].as_str().unwrap_or("Nan").to_string(),
+            obj_type: "csg".to_string(),
+            transform: if json["transform"].is_object() {
+                self.get_transform_from_json(&json["transform"])
+            } else {
+                Transform::default()
+            },
+            operation: match json["operation"].as_str().unwrap_or("union") {
+                "union" => CsgOperation::Union,
+                "intersection" => CsgOperation::Intersection,
+                "difference" => CsgOperation::Difference,
+                _ => return None,
+            },
+            left: self.get_object_from_json(&json["left"])?,
+            right: self.get_object_from_json(&json["right"])?,
+        });
+        csg.left.move_obj(csg.transform);
+        csg.right.move_obj(csg.transform);
+        Some(csg)
+    }
+
+    pub fn get_sdf_node_from_json(&self, json: &Value) -> Option<SdfNode> {
+        let center = self.get_vector_from_json(&json["center"]);
+        let size = if json["size"].is_object() {
+            Vector {
+                x: json["size"]["x"].as_f64().unwrap_or(1.0),
+                y: json["size"]["y"].as_f64().unwrap_or(1.0),
+                z: json["size"]["z"].as_f64().unwrap_or(1.0),
+            }
+        } else {
+            Vector {
+                x: 1.0,
+                y: 1.0,
+                z: 1.0,
+            }
+        };
+        let smoothness = json["smoothness"].as_f64().unwrap_or(0.5);
+
+        Some(match json["type"].as_str()? {
+            "sphere" => SdfNode::Sphere {
+                center,
+                radius: json["radius"].as_f64().unwrap_or(1.0),
+            },
+            "box" => SdfNode::Box {
+                center,
+                half_size: size / 2.0,
+            },
+            "round_box" => SdfNode::RoundBox {
+                center,
+                half_size: size / 2.0,
+                radius: json["radius"].as_f64().unwrap_or(0.1),
+            },
+            "torus" => SdfNode::Torus {
+                center,
+                major_radius: json["major_radius"].as_f64().unwrap_or(1.0),
+                minor_radius: json["minor_radius"].as_f64().unwrap_or(0.25),
+            },
+            "capsule" => SdfNode::Capsule {
+                start: self.get_vector_from_json(&json["start"]),
+                end: self.get_vector_from_json(&json["end"]),
+                radius: json["radius"].as_f64().unwrap_or(0.25),
+            },
+            "smooth_union" => SdfNode::SmoothUnion {
+                left: Box::new(self.get_sdf_node_from_json(&json["left"])?),
+                right: Box::new(self.get_sdf_node_from_json(&json["right"])?),
+                smoothness,
+            },
+            "smooth_subtract" => SdfNode::SmoothSubtract {
+                left: Box::new(self.get_sdf_node_from_json(&json["left"])?),
+                right: Box::new(self.get_sdf_node_from_json(&json["right"])?),
+                smoothness,
+            },
+            "smooth_intersect" => SdfNode::SmoothIntersect {
+                left: Box::new(self.get_sdf_node_from_json(&json["left"])?),
+                right: Box::new(self.get_sdf_node_from_json(&json["right"])?),
+                smoothness,
+            },
+            "twist" => SdfNode::Twist {
+                child: Box::new(self.get_sdf_node_from_json(&json["child"])?),
+                strength: json["strength"].as_f64().unwrap_or(1.0),
+            },
+            "repeat" => SdfNode::Repeat {
+                child: Box::new(self.get_sdf_node_from_json(&json["child"])?),
+                period: self.get_vector_from_json(&json["period"]),
+            },
+            _ => return None,
+        })
+    }
+
+    pub fn get_sdf_from_json(&self, json: &Value) -> Option<Box<Sdf>> {
+        let mut sdf = Sdf {
+            name: json["name"].as_str().unwrap_or("Nan").to_string(),
+            obj_type: "sdf".to_string(),
+            transform: if json["transform"].is_object() {
+                self.get_transform_from_json(&json["transform"])
+            } else {
+                Transform::default()
+            },
+            texture: if json["texture"].is_object() {
+                self.get_texture_from_json(&json["texture"])
+            } else {
+                Texture::default()
+            },
+            normal_map: if json["normal_map"].is_object() {
+                self.get_normal_map_from_json(&json["normal_map"])
+            } else {
+                Texture::normal_map_default()
+            },
+            shape: self.get_sdf_node_from_json(&json["shape"])?,
+            max_steps: json["max_steps"].as_u64().unwrap_or(256) as usize,
+            epsilon: json["epsilon"].as_f64().unwrap_or(1e-4),
+            max_distance: json["max_distance"].as_f64().unwrap_or(100.0),
+            axes: [Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            }; 3],
+            children: if json["children"].is_array() {
+                self.get_objects_from_json(&json["children"])
+            } else {
+                Vec::new()
+            },
+        };
+        sdf.apply_transform();
+        for i in 0..sdf.children.len() {
+            sdf.children[i].move_obj(sdf.transform);
+        }
+        Some(Box::new(sdf))
+    }
+
     pub fn get_object_from_json(&self, json: &Value) -> Option<Box<dyn Object + Send + Sync>> {
         if json["type"].is_string() {
             return match json["type"].as_str().unwrap() {
@@ -514,8 +870,19 @@ impl Parser {
                 "plane" => Some(self.get_plane_from_json(json)),
                 "cylinder" => Some(self.get_cylinder_from_json(json)),
                 "cone" => Some(self.get_cone_from_json(json)),
+                "torus" => Some(self.get_torus_from_json(json)),
+                "box" => Some(self.get_cuboid_from_json(json)),
+                "triangle" if json["displacement"].is_object() => {
+                    Some(self.get_displaced_triangle_from_json(json))
+                }
                 "triangle" => Some(self.get_triangle_from_json(json)),
                 "mesh" => Some(self.get_mesh_from_json(json)),
+                "sdf" => self
+                    .get_sdf_from_json(json)
+                    .map(|sdf| sdf as Box<dyn Object + Send + Sync>),
+                "csg" => self
+                    .get_csg_from_json(json)
+                    .map(|csg| csg as Box<dyn Object + Send + Sync>),
                 _ => None,
             };
         } else {
@@ -590,6 +957,45 @@ impl Parser {
         result
     }
 
+    pub fn get_spot_from_json(&self, json: &Value) -> Box<Spot> {
+        Box::new(Spot {
+            visible: json["visible"].as_bool().unwrap_or(false),
+            transform: if json["transform"].is_object() {
+                self.get_transform_from_json(&json["transform"])
+            } else {
+                Transform::default()
+            },
+            color: if json["color"].is_object() {
+                self.get_color_from_json(&json["color"])
+            } else {
+                Color::default()
+            },
+            strength: json["strength"].as_f64().unwrap_or(80.0),
+            radius: json["radius"].as_f64().unwrap_or(1.0),
+            falloff: json["falloff"].as_i64().unwrap_or(2) as i32,
+            inner_angle: json["inner_angle"].as_f64().unwrap_or(30.0),
+            outer_angle: json["outer_angle"].as_f64().unwrap_or(45.0),
+        })
+    }
+
+    pub fn get_area_from_json(&self, json: &Value, shape: AreaShape) -> Box<Area> {
+        Box::new(Area {
+            visible: json["visible"].as_bool().unwrap_or(false),
+            transform: if json["transform"].is_object() {
+                self.get_transform_from_json(&json["transform"])
+            } else {
+                Transform::default()
+            },
+            color: if json["color"].is_object() {
+                self.get_color_from_json(&json["color"])
+            } else {
+                Color::default()
+            },
+            strength: json["strength"].as_f64().unwrap_or(10.0),
+            shape,
+        })
+    }
+
     pub fn get_object_lights_from_json(&self, json: &Value) -> Vec<Box<dyn Light + Send + Sync>> {
         let mut lights: Vec<Box<dyn Light + Send + Sync>> = Vec::new();
 
@@ -603,6 +1009,28 @@ impl Parser {
                 lights.push(self.get_directional_from_json(point))
             }
         }
+        if json["spot"].is_array() {
+            for spot in json["spot"].as_array().unwrap().iter() {
+                lights.push(self.get_spot_from_json(spot))
+            }
+        }
+        if json["rectangle"].is_array() {
+            for rectangle in json["rectangle"].as_array().unwrap().iter() {
+                let shape = AreaShape::Rectangle {
+                    width: rectangle["width"].as_f64().unwrap_or(1.0),
+                    height: rectangle["height"].as_f64().unwrap_or(1.0),
+                };
+                lights.push(self.get_area_from_json(rectangle, shape))
+            }
+        }
+        if json["disk"].is_array() {
+            for disk in json["disk"].as_array().unwrap().iter() {
+                let shape = AreaShape::Disk {
+                    radius: disk["radius"].as_f64().unwrap_or(0.5),
+                };
+                lights.push(self.get_area_from_json(disk, shape))
+            }
+        }
         lights
     }
 
@@ -656,7 +1084,7 @@ impl Parser {
     }
 
     fn get_scene_from_json(&self, renderer: &mut Renderer, json: &Value, offset: Transform) {
-        if json["primitives"].is_object() {
+        if json["primitives"].is_array() {
             let mut primitives = self.get_objects_from_json(&json["primitives"]);
             self.move_primitives(&mut primitives, offset);
             renderer.primitives.extend(primitives);
@@ -676,17 +1104,95 @@ impl Parser {
             });
     }
 
+    /// Materials visible from a scene, its own ones shadow the inherited ones
+    fn get_materials_from_json(
+        &self,
+        json: &Value,
+        inherited: &Map<String, Value>,
+    ) -> Map<String, Value> {
+        let mut materials = inherited.clone();
+
+        if let Some(own) = json["materials"].as_object() {
+            for (name, material) in own.iter() {
+                materials.insert(name.clone(), material.clone());
+            }
+        }
+        materials
+    }
+
+    /// Texture fields of a reference to a material, either its name or an
+    /// object naming it in `material` next to the fields it overrides
+    fn get_material_from_json(
+        &self,
+        json: &Value,
+        materials: &Map<String, Value>,
+        depth: usize,
+    ) -> Value {
+        let (name, overrides) = match json {
+            Value::String(name) => (Some(name.as_str()), None),
+            Value::Object(fields) => (fields.get("material").and_then(Value::as_str), Some(fields)),
+            _ => return json.clone(),
+        };
+        let mut result = Map::new();
+
+        if let Some(name) = name {
+            // materials can be based on each other, but not in a loop
+            match materials.get(name) {
+                Some(material) if depth <= materials.len() => {
+                    if let Value::Object(fields) =
+                        self.get_material_from_json(material, materials, depth + 1)
+                    {
+                        result = fields;
+                    }
+                }
+                _ => println!("Unknown material \"{}\" !", name),
+            }
+        }
+        for (key, value) in overrides.into_iter().flatten() {
+            if key != "material" {
+                result.insert(key.clone(), value.clone());
+            }
+        }
+        Value::Object(result)
+    }
+
+    /// Replaces every material reference of a scene by the texture it names
+    fn resolve_materials(&self, json: &mut Value, materials: &Map<String, Value>) {
+        match json {
+            Value::Object(fields) => {
+                for (key, value) in fields.iter_mut() {
+                    if key == "texture" || key == "normal_map" {
+                        *value = self.get_material_from_json(value, materials, 0);
+                    } else if key != "materials" {
+                        self.resolve_materials(value, materials);
+                    }
+                }
+            }
+            Value::Array(values) => {
+                for value in values.iter_mut() {
+                    self.resolve_materials(value, materials);
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn get_scenes_from_json(
         &self,
         renderer: &mut Renderer,
         json: &Value,
+        materials: &Map<String, Value>,
         path_taken: &mut Vec<String>,
     ) {
         if json["scenes"].is_array() {
             for scene in json["scenes"].as_array().unwrap().iter() {
                 let filename = scene["file"].as_str().unwrap().to_string();
-                if scene["file"].is_string() && self.get_json(&filename).is_some() {
-                    let scene_json = self.get_json(&filename).unwrap();
+                if is_gltf_file(&filename) {
+                    self.get_gltf_scene(renderer, &filename, scene);
+                } else if scene["file"].is_string() && self.get_json(&filename).is_some() {
+                    let mut scene_json = self.get_json(&filename).unwrap();
+                    let materials = self.get_materials_from_json(&scene_json, materials);
+                    self.resolve_materials(&mut scene_json, &materials);
                     self.get_scene_from_json(
                         renderer,
                         &scene_json,
@@ -698,7 +1204,7 @@ impl Parser {
                     );
                     if !path_taken.contains(&filename) {
                         path_taken.push(filename);
-                        self.get_scenes_from_json(renderer, &scene_json, path_taken);
+                        self.get_scenes_from_json(renderer, &scene_json, &materials, path_taken);
                         path_taken.pop();
                     } else {
                         print!("inclusion of scene {} impossible\nbecause of configuration, it contains itself and will create an infinite loop if included\n the problem detected in the {} config file\n", filename, path_taken.last().unwrap_or(&"root".to_string()))
@@ -708,7 +1214,86 @@ impl Parser {
         }
     }
 
+    fn get_gltf_scene(&self, renderer: &mut Renderer, filename: &str, scene: &Value) {
+        let mut gltf = match GltfScene::open(filename) {
+            Some(gltf) => gltf,
+            None => {
+                println!("Invalid glTF file \"{}\" !", filename);
+                return;
+            }
+        };
+        let offset = if scene["transform"].is_object() {
+            self.get_transform_from_json(&scene["transform"])
+        } else {
+            Transform::default()
+        };
+        self.move_primitives(&mut gltf.primitives, offset);
+        self.move_lights(&mut gltf.lights, offset);
+        renderer.primitives.extend(gltf.primitives);
+        renderer.lights.lights.extend(gltf.lights);
+    }
+
+    pub fn get_renderer_from_gltf(&self, file: &str, height: i64, width: i64) -> Option<Renderer> {
+        let gltf = GltfScene::open(file)?;
+        let mut camera = self.get_camera_from_json(&Value::Null, height, width);
+
+        if let Some(gltf_camera) = gltf.camera {
+            camera.transform = gltf_camera.transform;
+            camera.fov = (2.0
+                * ((gltf_camera.yfov / 2.0).tan() * width as f64 / height as f64).atan())
+            .to_degrees()
+            .round() as i64;
+            camera.calculate_lens_distance();
+            camera.calculate_lens_size();
+        }
+        let mut renderer = Renderer {
+            camera,
+            primitives: gltf.primitives,
+            lights: Lights::default(),
+            skybox: Texture::default(),
+            environment: None,
+            sky: None,
+            bvh: Bvh::default(),
+            photon_map: None,
+        };
+        renderer.lights.lights.extend(gltf.lights);
+        renderer.build_bvh();
+        renderer.add_emissive_lights();
+        renderer.build_photon_map();
+        Some(renderer)
+    }
+
+    pub fn get_sky_from_json(&self, json: &Value) -> Sky {
+        Sky::new(
+            json["sun"]["elevation"].as_f64().unwrap_or(45.0),
+            json["sun"]["azimuth"].as_f64().unwrap_or(0.0),
+            json["turbidity"].as_f64().unwrap_or(3.0),
+            json["intensity"].as_f64().unwrap_or(1.0),
+        )
+    }
+
+    pub fn get_environment_from_json(&self, json: &Value) -> Option<Environment> {
+        let file = json["file"].as_str()?;
+        let mut environment = Environment::from_file(
+            file,
+            json["rotation"].as_f64().unwrap_or(0.0),
+            json["intensity"].as_f64().unwrap_or(1.0),
+        );
+
+        match environment.as_mut() {
+            Some(environment) => {
+                environment.samples = json["samples"].as_u64().unwrap_or(16) as usize;
+            }
+            None => println!("Couldn't read the environment map \"{}\" !", file),
+        }
+        environment
+    }
+
     pub fn get_renderer_from_json(&self, json: &Value, height: i64, width: i64) -> Renderer {
+        let materials = self.get_materials_from_json(json, &Map::new());
+        let mut resolved = json.clone();
+        self.resolve_materials(&mut resolved, &materials);
+        let json = &resolved;
         let mut renderer: Renderer = Renderer {
             camera: if json["camera"].is_object() {
                 self.get_camera_from_json(&json["camera"], height, width)
@@ -730,8 +1315,32 @@ impl Parser {
             } else {
                 Texture::default()
             },
+            environment: if json["environment"].is_object() {
+                self.get_environment_from_json(&json["environment"])
+            } else {
+                None
+            },
+            sky: if json["skybox"]["sky"].is_object() {
+                Some(self.get_sky_from_json(&json["skybox"]["sky"]))
+            } else {
+                None
+            },
+            bvh: Bvh::default(),
+            photon_map: None,
         };
-        self.get_scenes_from_json(&mut renderer, json, &mut Vec::new());
+        if let Some(sky) = &renderer.sky {
+            renderer.lights.lights.push(Box::new(sky.sun_light()));
+            let samples = json["skybox"]["sky"]["samples"].as_u64().unwrap_or(0) as usize;
+            if samples != 0 && renderer.environment.is_none() {
+                let mut environment = sky.environment(128, 64);
+                environment.samples = samples;
+                renderer.environment = Some(environment);
+            }
+        }
+        self.get_scenes_from_json(&mut renderer, json, &materials, &mut Vec::new());
+        renderer.build_bvh();
+        renderer.add_emissive_lights();
+        renderer.build_photon_map();
         renderer
     }
 
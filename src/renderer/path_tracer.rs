@@ -0,0 +1,365 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// path tracer
+//
+
+use super::brdf;
+use super::lights::{surface_response, LightSample};
+use super::primitives::Intersection;
+use super::types::Shading;
+use super::{Medium, Renderer};
+use crate::vector::Vector;
+use rand::Rng;
+use std::f64::consts::PI;
+
+/// Bounces a path always goes through before russian roulette may end it
+const ROULETTE_DEPTH: i64 = 3;
+
+/// Ways an opaque surface sends a path further
+struct Lobes {
+    /// Weight of the mirror reflection of Phong materials
+    mirror: Vector,
+    mirror_chance: f64,
+    /// Share of the other bounces drawn along the GGX lobe rather than
+    /// around the normal
+    glossy_chance: f64,
+    alpha: f64,
+}
+
+impl Lobes {
+    /// Density of a bounce drawn outside of the mirror reflection
+    fn pdf(&self, normal: Vector, view: Vector, direction: Vector) -> f64 {
+        let cos_theta = normal.dot_product(direction);
+        if cos_theta <= 0.0 {
+            return 0.0;
+        }
+        let mut pdf = (1.0 - self.glossy_chance) * cos_theta / PI;
+        if self.glossy_chance > 0.0 {
+            pdf +=
+                self.glossy_chance * brdf::ggx_reflection_pdf(normal, view, direction, self.alpha);
+        }
+        pdf * (1.0 - self.mirror_chance)
+    }
+}
+
+fn max_component(vector: Vector) -> f64 {
+    vector.x.max(vector.y).max(vector.z)
+}
+
+/// Veach's power heuristic, weight of a strategy against another one that
+/// could have drawn the same direction
+pub fn power_heuristic(pdf: f64, other: f64) -> f64 {
+    let (pdf, other) = (pdf * pdf, other * other);
+
+    if pdf + other == 0.0 {
+        0.0
+    } else {
+        pdf / (pdf + other)
+    }
+}
+
+impl Renderer {
+    /// Lights and environment that next event estimation picks from
+    fn light_strategies(&self) -> usize {
+        self.lights.lights.len() + self.environment.is_some() as usize
+    }
+
+    /// Whether a primitive stands on the way to a point `distance` away
+    pub(super) fn occluded(&self, origin: Vector, direction: Vector, distance: f64) -> bool {
+        let reach = distance * (1.0 - 1e-6);
+
+        self.bvh
+            .any_intersection(direction, origin, reach, |index| {
+                self.primitives[index]
+                    .intersection(direction, origin)
+                    .is_some_and(|hit| (hit.intersection_point - origin).len() < reach)
+            })
+    }
+
+    /// Light scattered along the path for a unit light coming from
+    /// `direction`, scaled by PI like `surface_response`
+    pub(super) fn scattering(
+        &self,
+        intersect: &Intersection,
+        ray: Vector,
+        direction: Vector,
+    ) -> Vector {
+        let texture = intersect.object.unwrap().get_texture();
+        if direction.dot_product(intersect.normal) <= 0.0 {
+            return Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            };
+        }
+        let response = surface_response(intersect, direction, ray, self.camera);
+
+        if texture.shading == Shading::Pbr {
+            response
+        } else {
+            response * (1.0 - texture.metalness)
+        }
+    }
+
+    fn lobes(&self, intersect: &Intersection) -> Lobes {
+        let object = intersect.object.unwrap();
+        let texture = object.get_texture();
+        if texture.shading == Shading::Pbr {
+            return Lobes {
+                mirror: Vector {
+                    x: 0.0,
+                    y: 0.0,
+                    z: 0.0,
+                },
+                mirror_chance: 0.0,
+                glossy_chance: (1.0 + texture.metalness) / 2.0,
+                alpha: brdf::alpha(texture.roughness),
+            };
+        }
+        let texture_coordinates =
+            object.surface_position(intersect.intersection_point - object.get_transform().pos);
+        let albedo = texture
+            .sample(
+                texture_coordinates.x,
+                texture_coordinates.y,
+                intersect.footprint,
+            )
+            .as_vector();
+        let mirror = albedo * texture.metalness + texture.specular * (1.0 - texture.metalness);
+        let diffuse = albedo * (self.camera.diffuse * texture.diffuse * (1.0 - texture.metalness));
+        let mirror_weight = max_component(mirror);
+
+        Lobes {
+            mirror,
+            mirror_chance: if mirror_weight > 0.0 {
+                mirror_weight / (mirror_weight + max_component(diffuse))
+            } else {
+                0.0
+            },
+            glossy_chance: 0.0,
+            alpha: 1.0,
+        }
+    }
+
+    /// Light reaching a surface straight from a light or the environment
+    /// picked at random, weighted against the bounce that could have found
+    /// it as well
+    fn next_event(&self, intersect: &Intersection, ray: Vector, lobes: &Lobes) -> Vector {
+        let nothing = Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let strategies = self.light_strategies();
+        if strategies == 0 {
+            return nothing;
+        }
+        let mut rng = rand::thread_rng();
+        let random = (rng.gen_range(0.0..1.0), rng.gen_range(0.0..1.0));
+        let normal = intersect.normal;
+        let origin = intersect.intersection_point + normal * self.camera.shadow_bias;
+        let sample = match self.lights.lights.get(rng.gen_range(0..strategies)) {
+            Some(light) => light.sample(origin, random, self.camera, &self.primitives),
+            None => self
+                .environment
+                .as_ref()
+                .and_then(|environment| environment.sample(random))
+                .filter(|(_, pdf)| *pdf > 0.0)
+                .map(|(direction, pdf)| LightSample {
+                    direction,
+                    distance: f64::INFINITY,
+                    radiance: self.background(direction),
+                    pdf,
+                }),
+        };
+        let sample = match sample {
+            Some(sample) if sample.direction.dot_product(normal) > 0.0 => sample,
+            _ => return nothing,
+        };
+        if self.occluded(origin, sample.direction, sample.distance) {
+            return nothing;
+        }
+        let light =
+            self.scattering(intersect, ray, sample.direction) * sample.radiance * strategies as f64;
+        if sample.pdf == 0.0 {
+            return light;
+        }
+        let bounce_pdf = lobes.pdf(normal, (ray * -1.0).normalize(), sample.direction);
+
+        light * (power_heuristic(sample.pdf / strategies as f64, bounce_pdf) / (PI * sample.pdf))
+    }
+
+    /// Sends a path through or off a transparent surface as the Fresnel
+    /// reflectance decides, keeping the stack of media up to date
+    pub(super) fn dielectric_bounce(
+        &self,
+        intersect: &Intersection,
+        ray: Vector,
+        media: &mut Vec<Medium>,
+    ) -> (Vector, Vector) {
+        let medium = Medium::from_texture(&intersect.object.unwrap().get_texture());
+        let mut normal = intersect.normal.normalize();
+        let entering = normal.dot_product(ray) < 0.0;
+        if !entering {
+            normal = normal * -1.0;
+        }
+        let (ior1, ior2, index) = medium.boundary(media, entering);
+        let reflectance = brdf::dielectric_fresnel(-normal.dot_product(ray), ior1, ior2);
+
+        if rand::thread_rng().gen_range(0.0..1.0) >= reflectance {
+            if let Some(refracted) = self.refract(normal, ray, ior1, ior2) {
+                if entering {
+                    media.push(medium);
+                } else if let Some(index) = index {
+                    media.remove(index);
+                }
+                return (
+                    intersect.intersection_point - normal * self.camera.shadow_bias,
+                    refracted,
+                );
+            }
+        }
+        (
+            intersect.intersection_point + normal * self.camera.shadow_bias,
+            ray - normal * 2.0 * ray.dot_product(normal),
+        )
+    }
+
+    /// Follows one path from the camera, the light of every surface met is
+    /// gathered with next event estimation and multiple importance sampling
+    pub(super) fn trace_path(&self, origin: Vector, ray: Vector) -> Vector {
+        let mut rng = rand::thread_rng();
+        let (mut origin, mut ray) = (origin, ray.normalize());
+        let mut throughput = Vector {
+            x: 1.0,
+            y: 1.0,
+            z: 1.0,
+        };
+        let mut radiance = Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let mut media: Vec<Medium> = Vec::new();
+        // density of the last bounce, none for camera rays and mirrors
+        let mut bounce_pdf: Option<f64> = None;
+        let strategies = self.light_strategies().max(1) as f64;
+        let bounces = self.camera.recursivity.max(1);
+
+        // the hit after the last bounce still gives its light
+        for depth in 0..=bounces {
+            let mut intersect = match self.found_nearest_intersection(origin, ray) {
+                Some(intersect) => intersect,
+                None => {
+                    let weight = match (bounce_pdf, &self.environment) {
+                        (Some(pdf), Some(environment)) => {
+                            power_heuristic(pdf, environment.pdf(ray) / strategies)
+                        }
+                        _ => 1.0,
+                    };
+                    radiance = radiance + throughput * self.background(ray) * weight;
+                    break;
+                }
+            };
+            if let Some(medium) = media.last() {
+                throughput = throughput
+                    * medium.transmittance((intersect.intersection_point - origin).len());
+            }
+
+            if let Some(light) = intersect.light {
+                if let Some((emitted, pdf)) = light.emission(origin, &intersect, &self.primitives) {
+                    let weight = match bounce_pdf {
+                        None => 1.0,
+                        Some(_) if pdf == 0.0 => 0.0,
+                        Some(bounce) => power_heuristic(bounce, pdf / strategies),
+                    };
+                    radiance = radiance + throughput * emitted * weight;
+                }
+                break;
+            }
+            let texture = intersect.object.unwrap().get_texture();
+            if texture.alpha < 1.0 && rng.gen_range(0.0..1.0) >= texture.alpha {
+                origin = intersect.intersection_point + ray * self.camera.shadow_bias;
+                continue;
+            }
+            if texture.emission_strength != 0.0 {
+                let weight = match bounce_pdf {
+                    None => 1.0,
+                    Some(bounce) => {
+                        let light_pdf: f64 = self
+                            .lights
+                            .lights
+                            .iter()
+                            .filter_map(|light| {
+                                light.emission(origin, &intersect, &self.primitives)
+                            })
+                            .map(|(_, pdf)| pdf)
+                            .sum();
+                        power_heuristic(bounce, light_pdf / strategies)
+                    }
+                };
+                radiance = radiance
+                    + throughput
+                        * texture.emission.as_vector()
+                        * texture.emission_strength
+                        * weight;
+            }
+            if depth == bounces {
+                break;
+            }
+            intersect.footprint = self.texture_footprint(&intersect, origin);
+            self.apply_normal_map(&mut intersect);
+
+            if texture.transmission > 0.0 && rng.gen_range(0.0..1.0) < texture.transmission {
+                (origin, ray) = self.dielectric_bounce(&intersect, ray, &mut media);
+                bounce_pdf = None;
+            } else {
+                let mut normal = intersect.normal.normalize();
+                if normal.dot_product(ray) > 0.0 {
+                    normal = normal * -1.0;
+                }
+                intersect.normal = normal;
+                let lobes = self.lobes(&intersect);
+                // photons only come from lights no bounce can reach, nothing
+                // is counted twice
+                radiance = radiance
+                    + throughput
+                        * (self.next_event(&intersect, ray, &lobes)
+                            + self.caustic_light(&intersect, ray));
+
+                let view = (ray * -1.0).normalize();
+                origin = intersect.intersection_point + normal * self.camera.shadow_bias;
+                if rng.gen_range(0.0..1.0) < lobes.mirror_chance {
+                    throughput = throughput * lobes.mirror / lobes.mirror_chance;
+                    ray = self.glossy_reflection(normal, ray, texture.roughness);
+                    bounce_pdf = None;
+                } else {
+                    let direction = if rng.gen_range(0.0..1.0) < lobes.glossy_chance {
+                        view.reflect(brdf::sample_ggx(normal, lobes.alpha))
+                    } else {
+                        brdf::sample_cosine(normal)
+                    };
+                    let pdf = lobes.pdf(normal, view, direction);
+                    if pdf <= 0.0 {
+                        break;
+                    }
+                    throughput =
+                        throughput * self.scattering(&intersect, ray, direction) / (PI * pdf);
+                    ray = direction;
+                    bounce_pdf = Some(pdf);
+                }
+            }
+
+            if depth >= ROULETTE_DEPTH {
+                let survival = max_component(throughput).min(0.95);
+                if rng.gen_range(0.0..1.0) >= survival {
+                    break;
+                }
+                throughput = throughput / survival;
+            }
+        }
+        radiance
+    }
+}
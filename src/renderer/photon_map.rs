@@ -0,0 +1,135 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// photon map
+//
+
+use super::types::BoundingBox;
+use crate::vector::Vector;
+
+/// Light left on a diffuse surface by a photon
+#[derive(Debug, Clone, Copy)]
+pub struct Photon {
+    pub position: Vector,
+    /// Direction the photon was travelling along when it landed
+    pub direction: Vector,
+    pub power: Vector,
+}
+
+/// Photons stored as a balanced kd-tree, every range of the array is a
+/// subtree whose middle photon splits the others along its axis
+pub struct PhotonMap {
+    photons: Vec<Photon>,
+    axes: Vec<u8>,
+}
+
+fn coordinate(vector: Vector, axis: u8) -> f64 {
+    match axis {
+        0 => vector.x,
+        1 => vector.y,
+        _ => vector.z,
+    }
+}
+
+fn build(photons: &mut [Photon], axes: &mut [u8]) {
+    if photons.len() <= 1 {
+        return;
+    }
+    let mut bounds = BoundingBox::default();
+    for photon in photons.iter() {
+        bounds.grow(photon.position);
+    }
+    let extent = bounds.max - bounds.min;
+    let axis = if extent.x >= extent.y && extent.x >= extent.z {
+        0
+    } else if extent.y >= extent.z {
+        1
+    } else {
+        2
+    };
+    let middle = photons.len() / 2;
+
+    photons.select_nth_unstable_by(middle, |a, b| {
+        coordinate(a.position, axis).total_cmp(&coordinate(b.position, axis))
+    });
+    axes[middle] = axis;
+    let (photons_below, photons_above) = photons.split_at_mut(middle);
+    let (axes_below, axes_above) = axes.split_at_mut(middle);
+    build(photons_below, axes_below);
+    build(&mut photons_above[1..], &mut axes_above[1..]);
+}
+
+impl PhotonMap {
+    pub fn new(mut photons: Vec<Photon>) -> PhotonMap {
+        let mut axes = vec![0; photons.len()];
+
+        build(&mut photons, &mut axes);
+        PhotonMap { photons, axes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.photons.is_empty()
+    }
+
+    fn search(
+        &self,
+        range: (usize, usize),
+        position: Vector,
+        count: usize,
+        max_distance2: &mut f64,
+        found: &mut Vec<(f64, usize)>,
+    ) {
+        let (start, end) = range;
+        if start >= end {
+            return;
+        }
+        let middle = start + (end - start) / 2;
+        let photon = &self.photons[middle];
+        let axis = self.axes[middle];
+        let delta = coordinate(position, axis) - coordinate(photon.position, axis);
+        let (near, far) = if delta < 0.0 {
+            ((start, middle), (middle + 1, end))
+        } else {
+            ((middle + 1, end), (start, middle))
+        };
+
+        self.search(near, position, count, max_distance2, found);
+        let distance2 = (photon.position - position).len2();
+        if distance2 < *max_distance2 {
+            let index = found.partition_point(|(other, _)| *other <= distance2);
+            found.insert(index, (distance2, middle));
+            found.truncate(count);
+            if found.len() == count {
+                *max_distance2 = found[count - 1].0;
+            }
+        }
+        if delta * delta < *max_distance2 {
+            self.search(far, position, count, max_distance2, found);
+        }
+    }
+
+    /// Up to `count` photons closest to `position` within `radius`, nearest
+    /// first, with the squared radius of the disk they were gathered in
+    pub fn nearest(&self, position: Vector, count: usize, radius: f64) -> (Vec<&Photon>, f64) {
+        let mut max_distance2 = radius * radius;
+        let mut found = Vec::with_capacity(count + 1);
+
+        if count > 0 {
+            self.search(
+                (0, self.photons.len()),
+                position,
+                count,
+                &mut max_distance2,
+                &mut found,
+            );
+        }
+        (
+            found
+                .iter()
+                .map(|(_, index)| &self.photons[*index])
+                .collect(),
+            max_distance2,
+        )
+    }
+}
@@ -1,9 +1,9 @@
 use crate::vector::{resolve_quadratic_equation, Vector};
 use serde::Serialize;
 
-use crate::renderer::types::{Texture, Transform};
+use crate::renderer::types::{BoundingBox, Texture, Transform};
 
-use super::{Intersection, Object};
+use super::{Intersection, Interval, Object, RayHit};
 
 #[derive(Serialize)]
 pub struct Cone {
@@ -52,6 +52,8 @@ impl Cone {
             normal,
             object: Some(self),
             light: None,
+            footprint: 0.0,
+            primitive: None,
         })
     }
 }
@@ -126,6 +128,8 @@ impl Object for Cone {
                     normal,
                     object: Some(self),
                     light: None,
+                    footprint: 0.0,
+                    primitive: None,
                 });
             } else {
                 return found_intersection;
@@ -144,6 +148,86 @@ impl Object for Cone {
         found_intersection
     }
 
+    fn intervals(&self, ray: Vector, origin: Vector) -> Vec<Interval<'_>> {
+        let along = ray.dot_product(self.axis);
+        let height = (origin - self.base).dot_product(self.axis);
+        // the solid stops at the apex even when scaled past it
+        let top_height = self
+            .height_applied
+            .min((self.top - self.base).dot_product(self.axis));
+        let mut caps = (
+            (f64::NEG_INFINITY, self.axis * -1.0),
+            (f64::INFINITY, self.axis),
+        );
+
+        // span between the base and the top
+        if along == 0.0 {
+            if height < 0.0 || height > top_height {
+                return Vec::new();
+            }
+        } else {
+            let bottom = -height / along;
+            let top = (top_height - height) / along;
+            caps = if bottom < top {
+                ((bottom, self.axis * -1.0), (top, self.axis))
+            } else {
+                ((top, self.axis), (bottom, self.axis * -1.0))
+            };
+        }
+        // spans inside the infinite double cone
+        let distance = origin - self.top;
+        let slope = 1.0 + self.radius_applied.powi(2) / self.height_applied.powi(2);
+        let a = ray.len2() - slope * along.powi(2);
+        let b = 2.0 * (ray.dot_product(distance) - slope * along * distance.dot_product(self.axis));
+        let c = distance.len2() - slope * distance.dot_product(self.axis).powi(2);
+        if a == 0.0 {
+            return Vec::new();
+        }
+        let result = resolve_quadratic_equation(a, b, c);
+        let side = |progress: f64| {
+            let point = origin + ray * progress;
+            let cos_angle = self.axis.dot_product(self.top - point);
+            (
+                progress,
+                (point - (self.top - self.axis * ((self.top - point).len2() / cos_angle)))
+                    .normalize(),
+            )
+        };
+        let spans = if result.len() == 2 {
+            let near = side(result[0].min(result[1]));
+            let far = side(result[0].max(result[1]));
+            if a > 0.0 {
+                vec![(near, far)]
+            } else {
+                vec![
+                    ((f64::NEG_INFINITY, self.axis), near),
+                    (far, (f64::INFINITY, self.axis)),
+                ]
+            }
+        } else if a > 0.0 {
+            return Vec::new();
+        } else {
+            vec![((f64::NEG_INFINITY, self.axis), (f64::INFINITY, self.axis))]
+        };
+        let mut intervals: Vec<Interval> = Vec::new();
+
+        for (mut enter, mut exit) in spans {
+            if caps.0 .0 > enter.0 {
+                enter = caps.0;
+            }
+            if caps.1 .0 < exit.0 {
+                exit = caps.1;
+            }
+            if enter.0 < exit.0 {
+                intervals.push(Interval {
+                    enter: RayHit::new(self, ray, origin, enter.0, enter.1),
+                    exit: RayHit::new(self, ray, origin, exit.0, exit.1),
+                });
+            }
+        }
+        intervals
+    }
+
     fn surface_position(&self, position: Vector) -> Vector {
         let mut rotated_position = position;
 
@@ -161,6 +245,28 @@ impl Object for Cone {
         }
     }
 
+    fn tangent(&self, position: Vector) -> Vector {
+        self.axis.cross_product(position)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        // the side is kept between base and base + height_applied around the apex at top
+        let end = self.base + self.axis * self.height_applied;
+        let apex_distance = (self.top - self.base)
+            .dot_product(self.axis)
+            .abs()
+            .max((self.top - end).dot_product(self.axis).abs());
+        let radius =
+            (apex_distance * self.radius_applied / self.height_applied).max(self.radius_applied);
+        let mut bounds = BoundingBox::from_disk(self.base, self.axis, radius)
+            .union(BoundingBox::from_disk(end, self.axis, radius));
+        bounds.grow(self.top);
+        for child in self.children.iter() {
+            bounds = bounds.union(child.bounding_box());
+        }
+        bounds
+    }
+
     fn get_transform(&self) -> Transform {
         self.transform
     }
@@ -170,6 +276,7 @@ impl Object for Cone {
         for child in self.children.iter_mut() {
             child.move_obj(offset);
         }
+        self.apply_transform();
     }
 
     fn get_name(&self) -> String {
@@ -0,0 +1,162 @@
+use crate::vector::Vector;
+use serde::Serialize;
+
+use crate::renderer::types::{BoundingBox, Texture, Transform};
+
+use super::{Intersection, Interval, Object, RayHit};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl CsgOperation {
+    fn keeps(&self, inside_left: bool, inside_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => inside_left || inside_right,
+            CsgOperation::Intersection => inside_left && inside_right,
+            CsgOperation::Difference => inside_left && !inside_right,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Csg {
+    pub name: String,
+    pub obj_type: String,
+    pub transform: Transform,
+    pub operation: CsgOperation,
+    pub left: Box<dyn Object + Send + Sync>,
+    pub right: Box<dyn Object + Send + Sync>,
+}
+
+/// Merges the spans of two solids, the boundaries of the right solid are
+/// turned inside out when they are carved out of the left one
+pub fn combine_intervals<'a>(
+    operation: CsgOperation,
+    left: Vec<Interval<'a>>,
+    right: Vec<Interval<'a>>,
+) -> Vec<Interval<'a>> {
+    let mut boundaries: Vec<(RayHit, bool, bool)> = Vec::new();
+
+    for interval in left {
+        boundaries.push((interval.enter, true, true));
+        boundaries.push((interval.exit, false, true));
+    }
+    for interval in right {
+        boundaries.push((interval.enter, true, false));
+        boundaries.push((interval.exit, false, false));
+    }
+    boundaries.sort_by(|a, b| a.0.progress.total_cmp(&b.0.progress));
+
+    let mut intervals: Vec<Interval> = Vec::new();
+    let mut enter: Option<RayHit> = None;
+    let (mut inside_left, mut inside_right) = (false, false);
+
+    for (mut hit, entering, from_left) in boundaries {
+        let was_inside = operation.keeps(inside_left, inside_right);
+        if from_left {
+            inside_left = entering;
+        } else {
+            inside_right = entering;
+        }
+        let is_inside = operation.keeps(inside_left, inside_right);
+        if was_inside == is_inside {
+            continue;
+        }
+        if !from_left && operation == CsgOperation::Difference {
+            hit.intersection.normal = hit.intersection.normal * -1.0;
+        }
+        if is_inside {
+            enter = Some(hit);
+        } else if let Some(enter_hit) = enter.take() {
+            intervals.push(Interval {
+                enter: enter_hit,
+                exit: hit,
+            });
+        }
+    }
+    intervals
+}
+
+impl Object for Csg {
+    fn apply_transform(&mut self) {}
+    fn get_texture(&self) -> Texture {
+        Texture::default()
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.transform
+    }
+
+    fn intersection(&self, ray: Vector, origin: Vector) -> Option<Intersection<'_>> {
+        for interval in self.intervals(ray, origin) {
+            if interval.enter.progress > 0.0 {
+                return Some(interval.enter.intersection);
+            }
+            // half spaces never leave, there is no surface to show there
+            if interval.exit.progress > 0.0 && interval.exit.progress.is_finite() {
+                return Some(interval.exit.intersection);
+            }
+        }
+        None
+    }
+
+    fn intervals(&self, ray: Vector, origin: Vector) -> Vec<Interval<'_>> {
+        combine_intervals(
+            self.operation,
+            self.left.intervals(ray, origin),
+            self.right.intervals(ray, origin),
+        )
+    }
+
+    fn move_obj(&mut self, offset: Transform) {
+        self.transform = self.transform + offset;
+        self.left.move_obj(offset);
+        self.right.move_obj(offset);
+    }
+
+    fn set_texture(&mut self, _new: Texture) {}
+    fn set_transform(&mut self, new: Transform) {
+        self.transform = new
+    }
+
+    fn surface_position(&self, _position: Vector) -> Vector {
+        Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        }
+    }
+
+    fn tangent(&self, _position: Vector) -> Vector {
+        Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        }
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        match self.operation {
+            CsgOperation::Union => self.left.bounding_box().union(self.right.bounding_box()),
+            CsgOperation::Intersection | CsgOperation::Difference => self.left.bounding_box(),
+        }
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_normal_map(&self) -> Texture {
+        Texture::normal_map_default()
+    }
+
+    fn get_type(&self) -> String {
+        self.obj_type.clone()
+    }
+
+    fn set_normal_map(&mut self, _new: Texture) {}
+}
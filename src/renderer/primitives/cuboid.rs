@@ -0,0 +1,248 @@
+use crate::vector::Vector;
+use serde::Serialize;
+
+use crate::renderer::types::{BoundingBox, Texture, Transform};
+
+use super::{Intersection, Interval, Object, RayHit};
+
+#[derive(Serialize)]
+pub struct Cuboid {
+    pub name: String,
+    pub obj_type: String,
+    pub transform: Transform,
+    pub texture: Texture,
+    pub normal_map: Texture,
+    pub size: Vector,
+    pub(crate) axes: [Vector; 3],
+    pub(crate) half_size_applied: [f64; 3],
+    pub children: Vec<Box<dyn Object + Send + Sync>>,
+}
+
+impl Cuboid {
+    fn local_position(&self, position: Vector) -> [f64; 3] {
+        [
+            position.dot_product(self.axes[0]),
+            position.dot_product(self.axes[1]),
+            position.dot_product(self.axes[2]),
+        ]
+    }
+
+    /// Index of the axis whose face contains the local position
+    fn face_axis(&self, local: [f64; 3]) -> usize {
+        let mut face = 0;
+
+        for axis in 1..3 {
+            if (local[axis] / self.half_size_applied[axis]).abs()
+                > (local[face] / self.half_size_applied[face]).abs()
+            {
+                face = axis;
+            }
+        }
+        face
+    }
+
+    /// Entry and exit of the ray through the slabs of the box with their normals
+    fn slabs(&self, ray: Vector, origin: Vector) -> Option<((f64, Vector), (f64, Vector))> {
+        let start = self.local_position(origin - self.transform.pos);
+        let direction = self.local_position(ray);
+        let mut t_min = f64::NEG_INFINITY;
+        let mut t_max = f64::INFINITY;
+        let mut normal_min = Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let mut normal_max = normal_min;
+
+        // slab test in the local frame of the box
+        for axis in 0..3 {
+            if direction[axis] == 0.0 {
+                if start[axis].abs() > self.half_size_applied[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let mut t_near = (-self.half_size_applied[axis] - start[axis]) / direction[axis];
+            let mut t_far = (self.half_size_applied[axis] - start[axis]) / direction[axis];
+            let mut normal = self.axes[axis] * -1.0;
+            if t_near > t_far {
+                std::mem::swap(&mut t_near, &mut t_far);
+                normal = self.axes[axis];
+            }
+            if t_near > t_min {
+                t_min = t_near;
+                normal_min = normal;
+            }
+            if t_far < t_max {
+                t_max = t_far;
+                normal_max = normal * -1.0;
+            }
+        }
+        if t_min > t_max {
+            return None;
+        }
+        Some(((t_min, normal_min), (t_max, normal_max)))
+    }
+}
+
+impl Object for Cuboid {
+    fn apply_transform(&mut self) {
+        let mut axes = [
+            Vector {
+                x: 1.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            Vector {
+                x: 0.0,
+                y: 1.0,
+                z: 0.0,
+            },
+            Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 1.0,
+            },
+        ];
+        for axis in axes.iter_mut() {
+            axis.rotate(
+                self.transform.rotation.x,
+                self.transform.rotation.y,
+                self.transform.rotation.z,
+            );
+        }
+        self.axes = axes;
+        self.half_size_applied = [
+            self.size.x * self.transform.scale / 2.0,
+            self.size.y * self.transform.scale / 2.0,
+            self.size.z * self.transform.scale / 2.0,
+        ];
+    }
+
+    fn intersection(&self, ray: Vector, origin: Vector) -> Option<Intersection<'_>> {
+        let mut found_intersection: Option<Intersection> = None;
+        let mut smallest_distance: f64 = f64::INFINITY;
+
+        for object in self.children.iter() {
+            if let Some(inters) = object.intersection(ray, origin) {
+                let distance_found = (inters.intersection_point - origin).len();
+                if distance_found < smallest_distance {
+                    smallest_distance = distance_found;
+                    found_intersection = Some(inters);
+                }
+            }
+        }
+        let ((t_min, normal_min), (t_max, normal_max)) = match self.slabs(ray, origin) {
+            Some(slabs) if slabs.1 .0 > 0.0 => slabs,
+            _ => return found_intersection,
+        };
+        let (progress, normal) = if t_min > 0.0 {
+            (t_min, normal_min)
+        } else {
+            (t_max, normal_max)
+        };
+        let intersection_point = origin + ray * progress;
+
+        if (intersection_point - origin).len() < smallest_distance {
+            found_intersection = Some(Intersection {
+                intersection_point,
+                normal,
+                object: Some(self),
+                light: None,
+                footprint: 0.0,
+                primitive: None,
+            })
+        }
+        found_intersection
+    }
+
+    fn intervals(&self, ray: Vector, origin: Vector) -> Vec<Interval<'_>> {
+        match self.slabs(ray, origin) {
+            Some(((t_min, normal_min), (t_max, normal_max))) if t_min < t_max => vec![Interval {
+                enter: RayHit::new(self, ray, origin, t_min, normal_min),
+                exit: RayHit::new(self, ray, origin, t_max, normal_max),
+            }],
+            _ => Vec::new(),
+        }
+    }
+
+    fn surface_position(&self, position: Vector) -> Vector {
+        let local = self.local_position(position);
+        let face = self.face_axis(local);
+        let u_axis = (face + 1) % 3;
+        let v_axis = (face + 2) % 3;
+        // faces on the negative side are seen from the other way around
+        let u = local[u_axis] * local[face].signum();
+
+        Vector {
+            x: u / (2.0 * self.half_size_applied[u_axis]) + 0.5,
+            y: local[v_axis] / (2.0 * self.half_size_applied[v_axis]) + 0.5,
+            z: 0.0,
+        }
+    }
+
+    fn tangent(&self, position: Vector) -> Vector {
+        let local = self.local_position(position);
+        let face = self.face_axis(local);
+
+        self.axes[(face + 1) % 3] * local[face].signum()
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::default();
+
+        for corner in 0..8 {
+            let mut point = self.transform.pos;
+            for axis in 0..3 {
+                let sign = if corner & (1 << axis) == 0 { -1.0 } else { 1.0 };
+                point = point + self.axes[axis] * (self.half_size_applied[axis] * sign);
+            }
+            bounds.grow(point);
+        }
+        for child in self.children.iter() {
+            bounds = bounds.union(child.bounding_box());
+        }
+        bounds
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.transform
+    }
+
+    fn move_obj(&mut self, offset: Transform) {
+        self.transform = self.transform + offset;
+        for child in self.children.iter_mut() {
+            child.move_obj(offset);
+        }
+        self.apply_transform();
+    }
+
+    fn set_transform(&mut self, new: Transform) {
+        self.transform = new;
+        self.apply_transform();
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_type(&self) -> String {
+        self.obj_type.clone()
+    }
+
+    fn get_texture(&self) -> Texture {
+        self.texture.clone()
+    }
+
+    fn set_texture(&mut self, new: Texture) {
+        self.texture = new
+    }
+
+    fn get_normal_map(&self) -> Texture {
+        self.normal_map.clone()
+    }
+
+    fn set_normal_map(&mut self, new: Texture) {
+        self.normal_map = new
+    }
+}
@@ -1,9 +1,9 @@
 use crate::vector::{resolve_quadratic_equation, Vector};
 use serde::Serialize;
 
-use crate::renderer::types::{Texture, Transform};
+use crate::renderer::types::{BoundingBox, Texture, Transform};
 
-use super::{Intersection, Object};
+use super::{Intersection, Interval, Object, RayHit};
 
 #[derive(Serialize)]
 pub struct Cylinder {
@@ -52,6 +52,8 @@ impl Cylinder {
             normal,
             object: Some(self),
             light: None,
+            footprint: 0.0,
+            primitive: None,
         })
     }
 }
@@ -124,6 +126,8 @@ impl Object for Cylinder {
                     normal,
                     object: Some(self),
                     light: None,
+                    footprint: 0.0,
+                    primitive: None,
                 });
             } else {
                 return found_intersection;
@@ -152,6 +156,67 @@ impl Object for Cylinder {
         found_intersection
     }
 
+    fn intervals(&self, ray: Vector, origin: Vector) -> Vec<Interval<'_>> {
+        let distance = origin - self.transform.pos;
+        let along = ray.dot_product(self.axis);
+        let height = distance.dot_product(self.axis);
+        let side_normal = |progress: f64| {
+            let point = distance + ray * progress;
+            point - self.axis * point.dot_product(self.axis)
+        };
+        let mut enter = (f64::NEG_INFINITY, self.axis * -1.0);
+        let mut exit = (f64::INFINITY, self.axis);
+
+        // span between the two caps
+        if along == 0.0 {
+            if height.abs() > self.height_applied / 2.0 {
+                return Vec::new();
+            }
+        } else {
+            let bottom = (-self.height_applied / 2.0 - height) / along;
+            let top = (self.height_applied / 2.0 - height) / along;
+            if bottom < top {
+                enter.0 = bottom;
+                exit.0 = top;
+            } else {
+                enter = (top, self.axis);
+                exit = (bottom, self.axis * -1.0);
+            }
+        }
+        // span inside the infinite tube
+        let a = ray.len2() - along.powi(2);
+        let c = distance.len2() - height.powi(2) - self.radius_applied.powi(2);
+        if a == 0.0 {
+            if c > 0.0 {
+                return Vec::new();
+            }
+        } else {
+            let result = resolve_quadratic_equation(
+                a,
+                2.0 * (ray.dot_product(distance) - along * height),
+                c,
+            );
+            if result.len() != 2 {
+                return Vec::new();
+            }
+            let near = result[0].min(result[1]);
+            let far = result[0].max(result[1]);
+            if near > enter.0 {
+                enter = (near, side_normal(near));
+            }
+            if far < exit.0 {
+                exit = (far, side_normal(far));
+            }
+        }
+        if enter.0 >= exit.0 {
+            return Vec::new();
+        }
+        vec![Interval {
+            enter: RayHit::new(self, ray, origin, enter.0, enter.1),
+            exit: RayHit::new(self, ray, origin, exit.0, exit.1),
+        }]
+    }
+
     fn surface_position(&self, position: Vector) -> Vector {
         let mut rotated_position = position;
 
@@ -169,6 +234,33 @@ impl Object for Cylinder {
         }
     }
 
+    fn tangent(&self, position: Vector) -> Vector {
+        self.axis.cross_product(position)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::from_disk(self.base, self.axis, self.radius_applied)
+            .union(BoundingBox::from_disk(
+                self.top,
+                self.axis,
+                self.radius_applied,
+            ))
+            .union(BoundingBox::from_disk(
+                self.transform.pos - self.axis * (self.height_applied / 2.0),
+                self.axis,
+                self.radius_applied,
+            ))
+            .union(BoundingBox::from_disk(
+                self.transform.pos + self.axis * (self.height_applied / 2.0),
+                self.axis,
+                self.radius_applied,
+            ));
+        for child in self.children.iter() {
+            bounds = bounds.union(child.bounding_box());
+        }
+        bounds
+    }
+
     fn get_transform(&self) -> Transform {
         self.transform
     }
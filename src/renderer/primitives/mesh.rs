@@ -5,12 +5,27 @@
 // mesh
 //
 
-use crate::renderer::primitives::{triangle::Triangle, Intersection, Object};
-use crate::renderer::types::{Texture, Transform};
+use crate::renderer::bvh::Bvh;
+use crate::renderer::primitives::{triangle::Triangle, Intersection, Interval, Object, RayHit};
+use crate::renderer::types::{BoundingBox, Color, Image, Texture, TexturesTypes, Transform};
 use crate::vector::Vector;
 use serde::Serialize;
+use std::collections::HashMap;
 use std::fs::OpenOptions;
 use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Each subdivision splits every triangle in four, past this the meshes
+/// don't fit in memory anymore
+pub const MAX_SUBDIVISIONS: u64 = 8;
+
+/// Relief applied to the vertices of a mesh along their normals
+#[derive(Debug, Clone)]
+pub struct Displacement {
+    pub texture: Texture,
+    pub scale: f64,
+    pub subdivisions: u64,
+}
 
 #[derive(Serialize)]
 pub struct Mesh {
@@ -20,80 +35,230 @@ pub struct Mesh {
     pub texture: Texture,
     pub normal_map: Texture,
     pub triangles: Vec<Triangle>,
+    #[serde(skip_serializing)]
+    pub(crate) bvh: Bvh,
     pub children: Vec<Box<dyn Object + Send + Sync>>,
 }
 
+/// Distance a point may stray from the faces it lies on, from rounding
+const FACE_MARGIN: f64 = 1e-6;
+
 impl Mesh {
-    pub fn parse_face(
-        &mut self,
-        line: String,
-        verteces: &Vec<Vector>,
-    ) -> (Option<Triangle>, Option<Triangle>) {
-        let points: Vec<&str> = line
-            .split_ascii_whitespace()
-            .filter(|&x| !x.is_empty())
-            .skip(1)
+    /// Triangle the point lies on, hits report their own triangle so this is
+    /// only needed when the mesh is asked directly
+    fn closest_face(&self, point: Vector) -> Option<&Triangle> {
+        let mut candidates: Vec<&Triangle> = Vec::new();
+
+        self.bvh.point_query(point, FACE_MARGIN, |index| {
+            candidates.push(&self.triangles[index])
+        });
+        candidates.into_iter().min_by(|a, b| {
+            // degenerate faces have no distance, they come last
+            let distance = |face: &Triangle| {
+                let weights = face.barycentric(point);
+                let distance = (point - face.point_a_applied)
+                    .dot_product(face.normal)
+                    .abs()
+                    - weights
+                        .iter()
+                        .fold(0.0_f64, |outside, weight| outside.min(*weight));
+                if distance.is_nan() {
+                    f64::INFINITY
+                } else {
+                    distance
+                }
+            };
+            distance(a).total_cmp(&distance(b))
+        })
+    }
+
+    /// Splits a triangle in four through the middles of its edges
+    fn subdivide(&self, triangle: &Triangle) -> [Triangle; 4] {
+        let [a, b, c] = triangle.corners();
+        let middle = |first: (Vector, Option<Vector>, Option<Vector>),
+                      second: (Vector, Option<Vector>, Option<Vector>)| {
+            (
+                (first.0 + second.0) / 2.0,
+                first.1.zip(second.1).map(|(n1, n2)| (n1 + n2).normalize()),
+                first.2.zip(second.2).map(|(uv1, uv2)| (uv1 + uv2) / 2.0),
+            )
+        };
+        let (ab, bc, ca) = (middle(a, b), middle(b, c), middle(c, a));
+
+        [
+            self.make_triangle([a, ab, ca], &triangle.texture),
+            self.make_triangle([ab, b, bc], &triangle.texture),
+            self.make_triangle([ca, bc, c], &triangle.texture),
+            self.make_triangle([ab, bc, ca], &triangle.texture),
+        ]
+    }
+
+    /// Tessellates the triangles and pushes their vertices along their normals
+    /// by the height read from the displacement texture, corners sharing a
+    /// position move together so the surface doesn't crack open
+    pub fn displace(&mut self, displacement: &Displacement) {
+        let mut triangles = std::mem::take(&mut self.triangles);
+
+        for _ in 0..displacement.subdivisions {
+            triangles = triangles
+                .iter()
+                .flat_map(|triangle| self.subdivide(triangle))
+                .collect();
+        }
+        let smooth = triangles
+            .iter()
+            .all(|triangle| triangle.vertex_normals.is_some());
+        // summed normal and height of every corner at a position, with their count
+        let mut shared: HashMap<(u64, u64, u64), (Vector, f64, f64)> = HashMap::new();
+
+        for triangle in triangles.iter() {
+            let corners = triangle.corners();
+            let face_normal =
+                (corners[1].0 - corners[0].0).cross_product(corners[2].0 - corners[0].0);
+            for (position, normal, uv) in corners {
+                // without coordinates, use the mapping the color lookup falls back on
+                let coordinates = uv.unwrap_or_else(|| triangle.surface_position(position));
+                let sum = shared.entry(Mesh::position_key(position)).or_insert((
+                    Vector {
+                        x: 0.0,
+                        y: 0.0,
+                        z: 0.0,
+                    },
+                    0.0,
+                    0.0,
+                ));
+                sum.0 = sum.0 + normal.map_or(face_normal, |normal| normal.normalize());
+                sum.1 += displacement.texture.height(coordinates.x, coordinates.y);
+                sum.2 += 1.0;
+            }
+        }
+        let displaced: Vec<Triangle> = triangles
+            .iter()
+            .map(|triangle| {
+                let moved = triangle.corners().map(|(position, _, uv)| {
+                    let (normal, height, count) = shared[&Mesh::position_key(position)];
+
+                    (
+                        position + normal.normalize() * (height / count) * displacement.scale,
+                        None,
+                        uv,
+                    )
+                });
+                self.make_triangle(moved, &triangle.texture)
+            })
             .collect();
-        let vertices_available = verteces.len();
-        let len = points.len();
-        if !(3..=4).contains(&len) {
-            return (None, None);
+        self.triangles = displaced;
+        if smooth {
+            Mesh::smooth_normals(&mut self.triangles);
         }
+    }
 
-        let mut points_res = [Vector {
-            x: 0.0,
-            y: 0.0,
-            z: 0.0,
-        }; 4];
-        let mut count = 0;
+    fn position_key(point: Vector) -> (u64, u64, u64) {
+        (point.x.to_bits(), point.y.to_bits(), point.z.to_bits())
+    }
 
-        for point in points {
-            let iter = &mut point.split('/');
+    /// Gives every vertex the area weighted average normal of the faces sharing it
+    fn smooth_normals(triangles: &mut [Triangle]) {
+        let mut normals: HashMap<(u64, u64, u64), Vector> = HashMap::new();
 
-            for (i, item) in iter.take(3).enumerate() {
-                if i == 1 && item.is_empty() {
-                    continue;
-                }
-                match item.parse::<usize>() {
-                    Ok(num) => {
-                        if i == 0 {
-                            if (num - 1) > vertices_available {
-                                return (None, None);
-                            }
-                            points_res[count] = verteces[num - 1];
-                        }
-                    }
-                    Err(_) => return (None, None),
-                }
+        for triangle in triangles.iter() {
+            let face = (triangle.point_b - triangle.point_a)
+                .cross_product(triangle.point_c - triangle.point_a);
+            for point in [triangle.point_a, triangle.point_b, triangle.point_c] {
+                let normal = normals.entry(Mesh::position_key(point)).or_insert(Vector {
+                    x: 0.0,
+                    y: 0.0,
+                    z: 0.0,
+                });
+                *normal = *normal + face;
             }
-            if iter.next().is_some() {
-                return (None, None);
+        }
+        for triangle in triangles.iter_mut() {
+            triangle.vertex_normals = Some(
+                [triangle.point_a, triangle.point_b, triangle.point_c]
+                    .map(|point| normals[&Mesh::position_key(point)].normalize()),
+            );
+            triangle.apply_transform();
+        }
+    }
+
+    pub fn build_bvh(&mut self) {
+        let boxes: Vec<BoundingBox> = self
+            .triangles
+            .iter()
+            .map(|face| face.bounding_box())
+            .collect();
+        self.bvh = Bvh::new(&boxes);
+    }
+
+    /// Converts a one based (or negative, counted from the end) OBJ index
+    fn parse_index(item: &str, available: usize) -> Option<usize> {
+        let index = item.parse::<i64>().ok()?;
+
+        if index > 0 && index as usize <= available {
+            Some(index as usize - 1)
+        } else if index < 0 && (-index) as usize <= available {
+            Some((available as i64 + index) as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Parses a face of any size into a fan of triangles
+    pub fn parse_face(
+        &mut self,
+        line: String,
+        verteces: &[Vector],
+        normals: &[Vector],
+        uvs: &[Vector],
+        texture: &Texture,
+    ) -> Option<Vec<Triangle>> {
+        let mut corners: Vec<(Vector, Option<Vector>, Option<Vector>)> = Vec::new();
+
+        for point in line.split_ascii_whitespace().skip(1) {
+            let mut items = point.split('/');
+            let vertex = verteces[Mesh::parse_index(items.next()?, verteces.len())?];
+            let uv = match items.next() {
+                Some(item) if !item.is_empty() => Some(uvs[Mesh::parse_index(item, uvs.len())?]),
+                _ => None,
+            };
+            let normal = match items.next() {
+                Some(item) if !item.is_empty() => {
+                    Some(normals[Mesh::parse_index(item, normals.len())?])
+                }
+                _ => None,
+            };
+            if items.next().is_some() {
+                return None;
             }
-            count += 1;
+            corners.push((vertex, normal, uv));
+        }
+        if corners.len() < 3 {
+            return None;
+        }
+
+        let mut triangles: Vec<Triangle> = Vec::new();
+        for i in 1..corners.len() - 1 {
+            triangles.push(self.make_triangle([corners[0], corners[i], corners[i + 1]], texture));
         }
+        Some(triangles)
+    }
 
-        points_res[0].rotate(
-            self.transform.rotation.x,
-            self.transform.rotation.y,
-            self.transform.rotation.z,
-        );
-        points_res[1].rotate(
-            self.transform.rotation.x,
-            self.transform.rotation.y,
-            self.transform.rotation.z,
-        );
-        points_res[2].rotate(
-            self.transform.rotation.x,
-            self.transform.rotation.y,
-            self.transform.rotation.z,
-        );
-        let mut fst_triangle: Triangle = Triangle {
+    /// Builds a triangle from its three corners given as (position, normal, uv),
+    /// the corners stay in object space and the triangle applies the mesh transform
+    pub fn make_triangle(
+        &self,
+        corners: [(Vector, Option<Vector>, Option<Vector>); 3],
+        texture: &Texture,
+    ) -> Triangle {
+        let [a, b, c] = corners;
+        let mut triangle: Triangle = Triangle {
             name: "Triangle From Mesh".to_string(),
             obj_type: "mesh".to_string(),
             transform: self.transform,
-            point_a: points_res[0],
-            point_b: points_res[1],
-            point_c: points_res[2],
+            point_a: a.0,
+            point_b: b.0,
+            point_c: c.0,
             point_a_applied: Vector {
                 x: 0.0,
                 y: 0.0,
@@ -109,57 +274,45 @@ impl Mesh {
                 y: 0.0,
                 z: 0.0,
             },
-            texture: self.texture.clone(),
+            texture: texture.clone(),
             normal: Vector {
                 x: 0.0,
                 y: 0.0,
                 z: 0.0,
             },
-            children: Vec::new(),
-            normal_map: self.normal_map.clone(),
-        };
-        fst_triangle.apply_transform();
-        if len == 3 {
-            return (Some(fst_triangle), None);
-        }
-        points_res[3].rotate(
-            self.transform.rotation.x,
-            self.transform.rotation.y,
-            self.transform.rotation.z,
-        );
-        let mut snd_triangle: Triangle = Triangle {
-            name: "Triangle From Mesh".to_string(),
-            obj_type: "mesh".to_string(),
-            transform: self.transform,
-            point_a: points_res[2],
-            point_b: points_res[3],
-            point_c: points_res[0],
-            point_a_applied: Vector {
-                x: 0.0,
-                y: 0.0,
-                z: 0.0,
+            vertex_normals: match (a.1, b.1, c.1) {
+                (Some(a), Some(b), Some(c)) => Some([a, b, c]),
+                _ => None,
             },
-            point_b_applied: Vector {
-                x: 0.0,
-                y: 0.0,
-                z: 0.0,
+            vertex_uvs: match (a.2, b.2, c.2) {
+                (Some(a), Some(b), Some(c)) => Some([a, b, c]),
+                _ => None,
             },
-            point_c_applied: Vector {
+            vertex_normals_applied: [Vector {
                 x: 0.0,
                 y: 0.0,
                 z: 0.0,
-            },
-            texture: self.texture.clone(),
-            normal: Vector {
-                x: 0.0,
-                y: 0.0,
-                z: 0.0,
-            },
+            }; 3],
             children: Vec::new(),
-            normal_map: Texture::normal_map_default(),
+            normal_map: self.normal_map.clone(),
         };
-        snd_triangle.apply_transform();
-        (Some(fst_triangle), Some(snd_triangle))
+        triangle.apply_transform();
+        triangle
+    }
+
+    /// Reads texture coordinates, u is turned around to run from the right
+    /// edge of images like the renderer does
+    pub fn parse_uv(&mut self, line: String) -> Option<Vector> {
+        let mut iter = line.split_ascii_whitespace().skip(1);
+
+        Some(Vector {
+            x: 1.0 - iter.next()?.parse::<f64>().ok()?,
+            y: match iter.next() {
+                Some(point) => point.parse::<f64>().ok()?,
+                None => 0.0,
+            },
+            z: 0.0,
+        })
     }
 
     pub fn parse_vertex(&mut self, line: String) -> Option<Vector> {
@@ -184,45 +337,166 @@ impl Mesh {
         }
         Some(new_vertex)
     }
+    /// Reads the materials of a MTL library, starting from the mesh texture
+    pub fn parse_mtl(&self, file_name: &str) -> HashMap<String, Texture> {
+        let mut materials: HashMap<String, Texture> = HashMap::new();
+        let file = match OpenOptions::new().read(true).open(file_name) {
+            Ok(file) => file,
+            Err(_) => {
+                println!("Cant open \"{}\" material file!", file_name);
+                return materials;
+            }
+        };
+        let directory = Path::new(file_name).parent().unwrap_or(Path::new(""));
+        // dissolve and illumination model of the current material, resolved once it is complete
+        let mut current: Option<(String, Texture, f64, bool)> = None;
+        let finish = |materials: &mut HashMap<String, Texture>,
+                      current: Option<(String, Texture, f64, bool)>| {
+            if let Some((name, mut texture, dissolve, refractive)) = current {
+                if refractive {
+                    texture.transmission = if dissolve < 1.0 { 1.0 - dissolve } else { 1.0 };
+                } else {
+                    texture.alpha = dissolve;
+                }
+                materials.insert(name, texture);
+            }
+        };
+
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            let values: Vec<&str> = line.split_ascii_whitespace().collect();
+            let number = |index: usize| {
+                values
+                    .get(index)
+                    .and_then(|value| value.parse::<f64>().ok())
+            };
+            let color = || {
+                Some(Color {
+                    r: number(1)? * 255.0,
+                    g: number(2).unwrap_or(number(1)?) * 255.0,
+                    b: number(3).unwrap_or(number(1)?) * 255.0,
+                })
+            };
+
+            if values.is_empty() {
+                continue;
+            }
+            if values[0] == "newmtl" {
+                finish(&mut materials, current.take());
+                let mut texture = self.texture.clone();
+                texture.texture_type = TexturesTypes::Color as u64;
+                current = Some((values[1..].join(" "), texture, 1.0, false));
+                continue;
+            }
+            let (_, texture, dissolve, refractive) = match current.as_mut() {
+                Some(material) => material,
+                None => continue,
+            };
+            match values[0] {
+                "Kd" => texture.color = color().unwrap_or(texture.color),
+                "Ks" => {
+                    if let Some(specular) = color() {
+                        texture.specular = (specular.r + specular.g + specular.b) / (3.0 * 255.0);
+                    }
+                }
+                "Ke" => {
+                    if let Some(emission) = color() {
+                        texture.emission = emission;
+                        texture.emission_strength = 1.0;
+                    }
+                }
+                "Ns" => texture.shininess = number(1).unwrap_or(texture.shininess),
+                "d" => *dissolve = number(1).unwrap_or(*dissolve),
+                "Tr" => *dissolve = 1.0 - number(1).unwrap_or(1.0 - *dissolve),
+                "Ni" => texture.ior = number(1).unwrap_or(texture.ior),
+                "illum" => {
+                    *refractive =
+                        matches!(number(1), Some(model) if [4.0, 6.0, 7.0, 9.0].contains(&model))
+                }
+                // options come before the file name, which is always last
+                "map_Kd" if values.len() > 1 => {
+                    let image = directory.join(values[values.len() - 1]);
+                    texture.texture_type = TexturesTypes::Image as u64;
+                    texture.image = Image::from_file(&image.to_string_lossy());
+                    texture.sampler.scale = Vector {
+                        x: 1.0,
+                        y: 1.0,
+                        z: 0.0,
+                    };
+                }
+                _ => {}
+            }
+        }
+        finish(&mut materials, current);
+        materials
+    }
+
     pub fn parse_obj(&mut self, file_name: &str) {
         let file = OpenOptions::new().read(true).open(file_name);
 
         if let Ok(obj) = file {
             let mut vertexes: Vec<Vector> = Vec::new();
+            let mut normals: Vec<Vector> = Vec::new();
+            let mut uvs: Vec<Vector> = Vec::new();
+            let directory = Path::new(file_name).parent().unwrap_or(Path::new(""));
+            let mut materials: HashMap<String, Texture> = HashMap::new();
+            let mut texture = self.texture.clone();
 
             for line in BufReader::new(obj).lines().flatten() {
-                if line.chars().all(|x| x.is_ascii_whitespace())
-                    || line.starts_with('#')
-                    || line.starts_with("o ")
-                    || line.starts_with("vn ")
-                    || line.starts_with("vt ")
-                    || line.starts_with("s ")
-                    || line.starts_with("mtllib ")
-                {
-                    continue;
-                } else if line.starts_with("v ") {
-                    if let Some(vertex) = self.parse_vertex(line) {
-                        vertexes.push(vertex);
-                    } else {
-                        println!("Invalid vertexes in \"{}\" !", file_name);
-                        return;
+                let statement = line
+                    .split_ascii_whitespace()
+                    .next()
+                    .unwrap_or("#")
+                    .to_string();
+
+                match statement.as_str() {
+                    "v" | "vn" => {
+                        if let Some(vertex) = self.parse_vertex(line) {
+                            if statement == "v" {
+                                vertexes.push(vertex);
+                            } else {
+                                normals.push(vertex);
+                            }
+                        } else {
+                            println!("Invalid vertexes in \"{}\" !", file_name);
+                            return;
+                        }
                     }
-                } else if line.starts_with("f ") {
-                    let face_parsed = self.parse_face(line, &vertexes);
-                    if let Some(face_fst) = face_parsed.0 {
-                        self.triangles.push(face_fst);
-                        if let Some(face_snd) = face_parsed.1 {
-                            self.triangles.push(face_snd);
+                    "vt" => {
+                        if let Some(uv) = self.parse_uv(line) {
+                            uvs.push(uv);
+                        } else {
+                            println!("Invalid texture coordinates in \"{}\" !", file_name);
+                            return;
                         }
-                    } else {
-                        println!("Invalid face in \"{}\" !", file_name);
-                        return;
                     }
-                } else {
-                    println!("Invalid \"{}\" mesh file!", file_name);
-                    return;
+                    "f" => {
+                        if let Some(faces) =
+                            self.parse_face(line, &vertexes, &normals, &uvs, &texture)
+                        {
+                            self.triangles.extend(faces);
+                        } else {
+                            println!("Invalid face in \"{}\" !", file_name);
+                            return;
+                        }
+                    }
+                    "mtllib" => {
+                        for library in line.split_ascii_whitespace().skip(1) {
+                            materials
+                                .extend(self.parse_mtl(&directory.join(library).to_string_lossy()));
+                        }
+                    }
+                    "usemtl" => {
+                        let name = line.split_ascii_whitespace().skip(1).collect::<Vec<_>>();
+                        texture = match materials.get(&name.join(" ")) {
+                            Some(material) => material.clone(),
+                            None => self.texture.clone(),
+                        };
+                    }
+                    // grouping and free-form statements don't change the geometry
+                    _ => {}
                 }
             }
+            self.build_bvh();
         } else {
             println!("Cant open \"{}\" mesh file!", file_name);
         }
@@ -235,10 +509,10 @@ impl Object for Mesh {
             face.transform = self.transform;
             face.apply_transform();
         }
+        self.build_bvh();
     }
 
     fn intersection(&self, ray: Vector, origin: Vector) -> Option<Intersection> {
-        let mut first_intersection: Option<Intersection> = None;
         let mut found_intersection: Option<Intersection> = None;
         let mut smallest_distance: f64 = f64::INFINITY;
 
@@ -252,19 +526,9 @@ impl Object for Mesh {
             }
         }
 
-        for face in &self.triangles {
-            if let Some(intersection) = face.intersection(ray, origin) {
-                if let Some(first) = &first_intersection {
-                    if (first.intersection_point - origin).len()
-                        > (intersection.intersection_point - origin).len()
-                    {
-                        first_intersection = Some(intersection);
-                    }
-                } else {
-                    first_intersection = Some(intersection);
-                }
-            }
-        }
+        let first_intersection = self.bvh.closest_intersection(ray, origin, |index| {
+            self.triangles[index].intersection(ray, origin)
+        });
         if first_intersection.is_some()
             && (first_intersection.as_ref().unwrap().intersection_point - origin).len()
                 < smallest_distance
@@ -274,12 +538,114 @@ impl Object for Mesh {
         found_intersection
     }
 
-    fn surface_position(&self, _: Vector) -> Vector {
-        Vector {
-            x: 0.5,
-            y: 0.5,
-            z: 0.0,
+    fn intervals(&self, ray: Vector, origin: Vector) -> Vec<Interval<'_>> {
+        let mut crossings: Vec<(f64, usize)> = Vec::new();
+
+        // the tree is walked both ways to find the spans behind the origin too
+        for (direction, ahead) in [(ray, true), (ray * -1.0, false)] {
+            self.bvh
+                .any_intersection(direction, origin, f64::INFINITY, |index| {
+                    if let Some(progress) = self.triangles[index].crossing(ray, origin) {
+                        if (progress >= 0.0) == ahead {
+                            crossings.push((progress, index));
+                        }
+                    }
+                    false
+                });
+        }
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // counting how deep the ray is keeps the spans right when it goes
+        // through an edge shared by two faces
+        let mut intervals: Vec<Interval> = Vec::new();
+        let mut enter: Option<RayHit> = None;
+        let mut depth = 0;
+        for (progress, index) in crossings {
+            let face = &self.triangles[index];
+            let facing = face.normal.dot_product(ray);
+            let hit = RayHit::new(
+                face,
+                ray,
+                origin,
+                progress,
+                face.shading_normal(origin + ray * progress),
+            );
+            if facing < 0.0 {
+                depth += 1;
+                if depth == 1 {
+                    enter = Some(hit);
+                }
+            } else if facing > 0.0 && depth > 0 {
+                depth -= 1;
+                if depth == 0 {
+                    if let Some(enter) = enter.take() {
+                        intervals.push(Interval { enter, exit: hit });
+                    }
+                }
+            }
+        }
+        intervals
+    }
+
+    fn surface_position(&self, position: Vector) -> Vector {
+        let point = position + self.transform.pos;
+
+        match self.closest_face(point) {
+            Some(face) => face.surface_position(point - face.transform.pos),
+            None => Vector {
+                x: 0.5,
+                y: 0.5,
+                z: 0.0,
+            },
+        }
+    }
+
+    fn tangent(&self, position: Vector) -> Vector {
+        let point = position + self.transform.pos;
+
+        match self.closest_face(point) {
+            Some(face) => face.tangent(point - face.transform.pos),
+            None => Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+        }
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::default();
+
+        for face in &self.triangles {
+            bounds = bounds.union(face.bounding_box());
+        }
+        for child in self.children.iter() {
+            bounds = bounds.union(child.bounding_box());
+        }
+        bounds
+    }
+
+    // every material of the file glows on its own, a light is made for each
+    fn emissive_bounds(&self) -> Vec<BoundingBox> {
+        let mut groups: Vec<(Vector, BoundingBox)> = Vec::new();
+
+        for face in &self.triangles {
+            let emission = face.texture.emission.as_vector() * face.texture.emission_strength;
+            if emission.len2() == 0.0 {
+                continue;
+            }
+            match groups.iter_mut().find(|(color, _)| {
+                color.x == emission.x && color.y == emission.y && color.z == emission.z
+            }) {
+                Some((_, bounds)) => *bounds = bounds.union(face.bounding_box()),
+                None => groups.push((emission, face.bounding_box())),
+            }
+        }
+        let mut bounds: Vec<BoundingBox> = groups.into_iter().map(|(_, bounds)| bounds).collect();
+        for child in self.children.iter() {
+            bounds.extend(child.emissive_bounds());
         }
+        bounds
     }
 
     fn get_transform(&self) -> Transform {
@@ -6,14 +6,18 @@
 //
 
 pub mod cone;
+pub mod csg;
+pub mod cuboid;
 pub mod cylinder;
 pub mod mesh;
 pub mod parent;
 pub mod plane;
+pub mod sdf;
 pub mod sphere;
+pub mod torus;
 pub mod triangle;
 
-use super::types::{Texture, Transform};
+use super::types::{BoundingBox, Texture, Transform};
 use crate::renderer::lights::Light;
 use crate::vector;
 use erased_serde::serialize_trait_object;
@@ -24,12 +28,69 @@ pub struct Intersection<'a> {
     pub normal: Vector,
     pub object: Option<&'a dyn Object>,
     pub light: Option<&'a dyn Light>,
+    /// Size of the pixel seen through the ray on the surface in texture space
+    pub footprint: f64,
+    /// Index of the top level object hit among the primitives of the
+    /// renderer, meshes, CSG nodes and parents report their inner parts as
+    /// `object`
+    pub primitive: Option<usize>,
+}
+
+/// A boundary crossed by a ray, `progress` is the ray parameter of the hit
+pub struct RayHit<'a> {
+    pub progress: f64,
+    pub intersection: Intersection<'a>,
+}
+
+impl<'a> RayHit<'a> {
+    pub fn new(
+        object: &'a dyn Object,
+        ray: Vector,
+        origin: Vector,
+        progress: f64,
+        normal: Vector,
+    ) -> RayHit<'a> {
+        RayHit {
+            progress,
+            intersection: Intersection {
+                intersection_point: origin + ray * progress,
+                normal,
+                object: Some(object),
+                light: None,
+                footprint: 0.0,
+                primitive: None,
+            },
+        }
+    }
+}
+
+/// A span of a ray inside a solid, between the entry and the exit hits
+pub struct Interval<'a> {
+    pub enter: RayHit<'a>,
+    pub exit: RayHit<'a>,
 }
 
 pub trait Object: erased_serde::Serialize {
     fn apply_transform(&mut self);
     fn intersection(&self, ray: Vector, origin: Vector) -> Option<Intersection>;
+    /// Every span of the ray inside the solid sorted along the ray, including
+    /// the ones behind the origin, surfaces without an inside return none
+    fn intervals(&self, ray: Vector, origin: Vector) -> Vec<Interval<'_>>;
     fn surface_position(&self, position: Vector) -> Vector;
+    /// Direction in which the first texture coordinate grows, it orients normal maps
+    fn tangent(&self, position: Vector) -> Vector;
+    fn bounding_box(&self) -> BoundingBox;
+    /// Bounds of each part of the object giving off light, emissive lights
+    /// are sampled through them
+    fn emissive_bounds(&self) -> Vec<BoundingBox> {
+        let texture = self.get_texture();
+
+        if texture.emission_strength == 0.0 || texture.emission.as_vector().len2() == 0.0 {
+            Vec::new()
+        } else {
+            vec![self.bounding_box()]
+        }
+    }
     fn get_transform(&self) -> Transform;
     fn move_obj(&mut self, offset: Transform);
     fn set_transform(&mut self, new: Transform);
@@ -1,9 +1,12 @@
 use crate::vector::Vector;
 use serde::Serialize;
 
-use crate::renderer::types::{Texture, Transform};
+use crate::renderer::types::{BoundingBox, Texture, Transform};
 
-use super::{Intersection, Object};
+use super::{
+    csg::{combine_intervals, CsgOperation},
+    Intersection, Interval, Object,
+};
 
 #[derive(Serialize)]
 pub struct Parent {
@@ -39,6 +42,16 @@ impl Object for Parent {
         found_intersection
     }
 
+    fn intervals(&self, ray: Vector, origin: Vector) -> Vec<Interval<'_>> {
+        self.children.iter().fold(Vec::new(), |intervals, object| {
+            combine_intervals(
+                CsgOperation::Union,
+                intervals,
+                object.intervals(ray, origin),
+            )
+        })
+    }
+
     fn move_obj(&mut self, offset: Transform) {
         self.transform = self.transform + offset;
         for child in self.children.iter_mut() {
@@ -59,6 +72,23 @@ impl Object for Parent {
         }
     }
 
+    fn tangent(&self, _position: Vector) -> Vector {
+        Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        }
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::default();
+
+        for child in self.children.iter() {
+            bounds = bounds.union(child.bounding_box());
+        }
+        bounds
+    }
+
     fn get_name(&self) -> String {
         self.name.clone()
     }
@@ -1,9 +1,9 @@
 use crate::vector::Vector;
 use serde::Serialize;
 
-use crate::renderer::types::{Texture, Transform};
+use crate::renderer::types::{BoundingBox, Texture, Transform};
 
-use super::{Intersection, Object};
+use super::{Intersection, Interval, Object, RayHit};
 
 #[derive(Serialize)]
 pub struct Plane {
@@ -59,11 +59,43 @@ impl Object for Plane {
                 normal: self.normal_applied,
                 object: Some(self),
                 light: None,
+                footprint: 0.0,
+                primitive: None,
             })
         }
         found_intersection
     }
 
+    /// The solid is the half space behind the normal, its spans run to
+    /// infinity on the other side
+    fn intervals(&self, ray: Vector, origin: Vector) -> Vec<Interval<'_>> {
+        let facing = ray.dot_product(self.normal_applied);
+        let height = (origin - self.transform.pos).dot_product(self.normal_applied);
+        let bound = |progress: f64| RayHit::new(self, ray, origin, progress, self.normal_applied);
+
+        if facing == 0.0 {
+            if height < 0.0 {
+                return vec![Interval {
+                    enter: bound(f64::NEG_INFINITY),
+                    exit: bound(f64::INFINITY),
+                }];
+            }
+            return Vec::new();
+        }
+        let progress = -height / facing;
+        if facing < 0.0 {
+            vec![Interval {
+                enter: bound(progress),
+                exit: bound(f64::INFINITY),
+            }]
+        } else {
+            vec![Interval {
+                enter: bound(f64::NEG_INFINITY),
+                exit: bound(progress),
+            }]
+        }
+    }
+
     fn surface_position(&self, position: Vector) -> Vector {
         let mut rotated_position = position;
 
@@ -79,6 +111,18 @@ impl Object for Plane {
         }
     }
 
+    fn tangent(&self, _position: Vector) -> Vector {
+        Vector {
+            x: 1.0,
+            y: 0.0,
+            z: 0.0,
+        }
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::infinite()
+    }
+
     fn get_transform(&self) -> Transform {
         self.transform
     }
@@ -0,0 +1,569 @@
+use crate::vector::Vector;
+use serde::Serialize;
+
+use crate::renderer::types::{BoundingBox, Texture, Transform};
+
+use super::{Intersection, Interval, Object, RayHit};
+
+#[derive(Debug, Clone, Serialize)]
+pub enum SdfNode {
+    Sphere {
+        center: Vector,
+        radius: f64,
+    },
+    Box {
+        center: Vector,
+        half_size: Vector,
+    },
+    RoundBox {
+        center: Vector,
+        half_size: Vector,
+        radius: f64,
+    },
+    Torus {
+        center: Vector,
+        major_radius: f64,
+        minor_radius: f64,
+    },
+    Capsule {
+        start: Vector,
+        end: Vector,
+        radius: f64,
+    },
+    SmoothUnion {
+        left: Box<SdfNode>,
+        right: Box<SdfNode>,
+        smoothness: f64,
+    },
+    SmoothSubtract {
+        left: Box<SdfNode>,
+        right: Box<SdfNode>,
+        smoothness: f64,
+    },
+    SmoothIntersect {
+        left: Box<SdfNode>,
+        right: Box<SdfNode>,
+        smoothness: f64,
+    },
+    /// Rotates the child around z by `strength` radians per unit of height
+    Twist {
+        child: Box<SdfNode>,
+        strength: f64,
+    },
+    /// Repeats the child every `period` along each axis, zero disables an axis
+    Repeat {
+        child: Box<SdfNode>,
+        period: Vector,
+    },
+}
+
+fn abs(vector: Vector) -> Vector {
+    Vector {
+        x: vector.x.abs(),
+        y: vector.y.abs(),
+        z: vector.z.abs(),
+    }
+}
+
+fn max(vector: Vector, value: f64) -> Vector {
+    Vector {
+        x: vector.x.max(value),
+        y: vector.y.max(value),
+        z: vector.z.max(value),
+    }
+}
+
+fn box_distance(position: Vector, half_size: Vector) -> f64 {
+    let q = abs(position) - half_size;
+
+    max(q, 0.0).len() + q.x.max(q.y).max(q.z).min(0.0)
+}
+
+fn repeat(value: f64, period: f64) -> f64 {
+    if period > 0.0 {
+        value - period * (value / period).round()
+    } else {
+        value
+    }
+}
+
+/// Polynomial smooth blend, returns the interpolation weight and the bulge
+fn blend(a: f64, b: f64, smoothness: f64) -> (f64, f64) {
+    if smoothness <= 0.0 {
+        return (if a < b { 1.0 } else { 0.0 }, 0.0);
+    }
+    let h = (0.5 + 0.5 * (b - a) / smoothness).clamp(0.0, 1.0);
+
+    (h, smoothness * h * (1.0 - h))
+}
+
+impl SdfNode {
+    pub fn distance(&self, position: Vector) -> f64 {
+        match self {
+            SdfNode::Sphere { center, radius } => (position - *center).len() - radius,
+            SdfNode::Box { center, half_size } => box_distance(position - *center, *half_size),
+            SdfNode::RoundBox {
+                center,
+                half_size,
+                radius,
+            } => box_distance(position - *center, *half_size - *radius) - radius,
+            SdfNode::Torus {
+                center,
+                major_radius,
+                minor_radius,
+            } => {
+                let local = position - *center;
+                let ring = (local.x.powi(2) + local.y.powi(2)).sqrt() - major_radius;
+
+                (ring.powi(2) + local.z.powi(2)).sqrt() - minor_radius
+            }
+            SdfNode::Capsule { start, end, radius } => {
+                let segment = *end - *start;
+                let local = position - *start;
+                let progress = if segment.len2() == 0.0 {
+                    0.0
+                } else {
+                    (local.dot_product(segment) / segment.len2()).clamp(0.0, 1.0)
+                };
+
+                (local - segment * progress).len() - radius
+            }
+            SdfNode::SmoothUnion {
+                left,
+                right,
+                smoothness,
+            } => {
+                let (a, b) = (left.distance(position), right.distance(position));
+                let (h, bulge) = blend(a, b, *smoothness);
+
+                b + (a - b) * h - bulge
+            }
+            SdfNode::SmoothSubtract {
+                left,
+                right,
+                smoothness,
+            } => {
+                let (a, b) = (left.distance(position), -right.distance(position));
+                let (h, bulge) = blend(-a, -b, *smoothness);
+
+                b + (a - b) * h + bulge
+            }
+            SdfNode::SmoothIntersect {
+                left,
+                right,
+                smoothness,
+            } => {
+                let (a, b) = (left.distance(position), right.distance(position));
+                let (h, bulge) = blend(-a, -b, *smoothness);
+
+                b + (a - b) * h + bulge
+            }
+            SdfNode::Twist { child, strength } => {
+                let (sin, cos) = (strength * position.z).sin_cos();
+                let twisted = Vector {
+                    x: cos * position.x - sin * position.y,
+                    y: sin * position.x + cos * position.y,
+                    z: position.z,
+                };
+                // the twist stretches space, shorten the step to stay below the surface
+                let stretch = (1.0
+                    + (strength * (position.x.powi(2) + position.y.powi(2)).sqrt()).powi(2))
+                .sqrt();
+
+                child.distance(twisted) / stretch
+            }
+            SdfNode::Repeat { child, period } => child.distance(Vector {
+                x: repeat(position.x, period.x),
+                y: repeat(position.y, period.y),
+                z: repeat(position.z, period.z),
+            }),
+        }
+    }
+
+    /// Local space box containing the surface
+    pub fn bounds(&self) -> BoundingBox {
+        match self {
+            SdfNode::Sphere { center, radius } => {
+                BoundingBox::from_points(&[*center - *radius, *center + *radius])
+            }
+            SdfNode::Box { center, half_size }
+            | SdfNode::RoundBox {
+                center, half_size, ..
+            } => BoundingBox::from_points(&[*center - *half_size, *center + *half_size]),
+            SdfNode::Torus {
+                center,
+                major_radius,
+                minor_radius,
+            } => {
+                let extent = Vector {
+                    x: major_radius + minor_radius,
+                    y: major_radius + minor_radius,
+                    z: *minor_radius,
+                };
+                BoundingBox::from_points(&[*center - extent, *center + extent])
+            }
+            SdfNode::Capsule { start, end, radius } => BoundingBox::from_points(&[
+                *start - *radius,
+                *start + *radius,
+                *end - *radius,
+                *end + *radius,
+            ]),
+            SdfNode::SmoothUnion {
+                left,
+                right,
+                smoothness,
+            } => {
+                let bounds = left.bounds().union(right.bounds());
+                BoundingBox::from_points(&[
+                    bounds.min - smoothness / 4.0,
+                    bounds.max + smoothness / 4.0,
+                ])
+            }
+            SdfNode::SmoothSubtract { left, .. } | SdfNode::SmoothIntersect { left, .. } => {
+                left.bounds()
+            }
+            SdfNode::Twist { child, .. } => {
+                let bounds = child.bounds();
+                if !bounds.is_finite() {
+                    return bounds;
+                }
+                let corner = (bounds.min.x.abs().max(bounds.max.x.abs()).powi(2)
+                    + bounds.min.y.abs().max(bounds.max.y.abs()).powi(2))
+                .sqrt();
+                BoundingBox::from_points(&[
+                    Vector {
+                        x: -corner,
+                        y: -corner,
+                        z: bounds.min.z,
+                    },
+                    Vector {
+                        x: corner,
+                        y: corner,
+                        z: bounds.max.z,
+                    },
+                ])
+            }
+            SdfNode::Repeat { .. } => BoundingBox::infinite(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Sdf {
+    pub name: String,
+    pub obj_type: String,
+    pub transform: Transform,
+    pub texture: Texture,
+    pub normal_map: Texture,
+    pub shape: SdfNode,
+    pub max_steps: usize,
+    pub epsilon: f64,
+    pub max_distance: f64,
+    pub(crate) axes: [Vector; 3],
+    pub children: Vec<Box<dyn Object + Send + Sync>>,
+}
+
+impl Sdf {
+    /// Distance from a world space position to the surface
+    fn distance(&self, position: Vector) -> f64 {
+        let relative = position - self.transform.pos;
+        let local = Vector {
+            x: relative.dot_product(self.axes[0]),
+            y: relative.dot_product(self.axes[1]),
+            z: relative.dot_product(self.axes[2]),
+        };
+
+        self.shape.distance(local / self.transform.scale) * self.transform.scale
+    }
+
+    /// World space box containing the surface, without the children
+    fn shape_bounds(&self) -> BoundingBox {
+        let local = self.shape.bounds();
+        let mut bounds = BoundingBox::default();
+
+        if !local.is_finite() {
+            return BoundingBox::infinite();
+        }
+        for corner in 0..8 {
+            let point = Vector {
+                x: if corner & 1 == 0 {
+                    local.min.x
+                } else {
+                    local.max.x
+                },
+                y: if corner & 2 == 0 {
+                    local.min.y
+                } else {
+                    local.max.y
+                },
+                z: if corner & 4 == 0 {
+                    local.min.z
+                } else {
+                    local.max.z
+                },
+            } * self.transform.scale;
+            bounds.grow(
+                self.transform.pos
+                    + self.axes[0] * point.x
+                    + self.axes[1] * point.y
+                    + self.axes[2] * point.z,
+            );
+        }
+        bounds
+    }
+
+    fn gradient(&self, position: Vector) -> Vector {
+        let step = self.epsilon;
+        let along = |x: f64, y: f64, z: f64| {
+            let offset = Vector { x, y, z };
+            self.distance(position + offset) - self.distance(position - offset)
+        };
+
+        Vector {
+            x: along(step, 0.0, 0.0),
+            y: along(0.0, step, 0.0),
+            z: along(0.0, 0.0, step),
+        }
+        .normalize()
+    }
+
+    /// Bisects the surface crossing between two progresses along a line,
+    /// the distance changes sign between them, never lands past the surface
+    /// so rays leaving an exit don't find it again ahead of them
+    fn crossing(&self, origin: Vector, direction: Vector, before: f64, after: f64) -> f64 {
+        let inside = self.distance(origin + direction * before) < 0.0;
+        let (mut before, mut after) = (before, after);
+
+        while after - before > self.epsilon {
+            let middle = (before + after) / 2.0;
+            if (self.distance(origin + direction * middle) < 0.0) == inside {
+                before = middle;
+            } else {
+                after = middle;
+            }
+        }
+        before
+    }
+
+    /// Local axis the texture is projected along, the one the surface faces the most
+    fn projection_axis(&self, position: Vector) -> usize {
+        let normal = self.gradient(position + self.transform.pos);
+        let weights = [
+            normal.dot_product(self.axes[0]).abs(),
+            normal.dot_product(self.axes[1]).abs(),
+            normal.dot_product(self.axes[2]).abs(),
+        ];
+
+        if weights[0] >= weights[1] && weights[0] >= weights[2] {
+            0
+        } else if weights[1] >= weights[2] {
+            1
+        } else {
+            2
+        }
+    }
+}
+
+impl Object for Sdf {
+    fn apply_transform(&mut self) {
+        let mut axes = [
+            Vector {
+                x: 1.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            Vector {
+                x: 0.0,
+                y: 1.0,
+                z: 0.0,
+            },
+            Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 1.0,
+            },
+        ];
+        for axis in axes.iter_mut() {
+            axis.rotate(
+                self.transform.rotation.x,
+                self.transform.rotation.y,
+                self.transform.rotation.z,
+            );
+        }
+        self.axes = axes;
+    }
+
+    fn intersection(&self, ray: Vector, origin: Vector) -> Option<Intersection<'_>> {
+        let mut found_intersection: Option<Intersection> = None;
+        let mut smallest_distance: f64 = f64::INFINITY;
+
+        for object in self.children.iter() {
+            if let Some(inters) = object.intersection(ray, origin) {
+                let distance_found = (inters.intersection_point - origin).len();
+                if distance_found < smallest_distance {
+                    smallest_distance = distance_found;
+                    found_intersection = Some(inters);
+                }
+            }
+        }
+        let direction = ray.normalize();
+        let entry = match self.shape_bounds().intersection(direction, origin) {
+            Some(entry) => entry,
+            None => return found_intersection,
+        };
+        // leave the surface the ray may start on before deciding which side it marches in
+        let mut progress = entry.max(self.epsilon * 2.0);
+        let side = self.distance(origin + direction * progress).signum();
+
+        for _ in 0..self.max_steps {
+            if progress > self.max_distance.min(smallest_distance) {
+                break;
+            }
+            let intersection_point = origin + direction * progress;
+            let distance = self.distance(intersection_point) * side;
+            if distance < self.epsilon {
+                return Some(Intersection {
+                    intersection_point,
+                    normal: self.gradient(intersection_point),
+                    object: Some(self),
+                    light: None,
+                    footprint: 0.0,
+                    primitive: None,
+                });
+            }
+            progress += distance;
+        }
+        found_intersection
+    }
+
+    fn intervals(&self, ray: Vector, origin: Vector) -> Vec<Interval<'_>> {
+        let direction = ray.normalize();
+        let bounds = self.shape_bounds();
+        let reach = self.max_distance;
+        // the line through the bounds, in front of the origin and behind it
+        let (start, end) = match (
+            bounds.intersection(direction, origin - direction * reach),
+            bounds.intersection(direction * -1.0, origin + direction * reach),
+        ) {
+            (Some(enter), Some(exit)) => (enter - reach, reach - exit),
+            _ => return Vec::new(),
+        };
+        let hit = |progress: f64| {
+            let point = origin + direction * progress;
+            RayHit::new(
+                self,
+                ray,
+                origin,
+                progress / ray.len(),
+                self.gradient(point),
+            )
+        };
+        let mut intervals = Vec::new();
+        let mut progress = start;
+        let mut distance = self.distance(origin + direction * progress);
+        let mut enter = if distance < 0.0 {
+            Some(hit(progress))
+        } else {
+            None
+        };
+        let mut steps = 0;
+
+        while progress < end && steps < self.max_steps {
+            let previous = progress;
+            progress += distance.abs().max(self.epsilon);
+            distance = self.distance(origin + direction * progress);
+            steps += 1;
+            if progress >= end {
+                break;
+            }
+            if (distance < 0.0) == enter.is_some() {
+                continue;
+            }
+            let crossing = hit(self.crossing(origin, direction, previous, progress));
+            match enter.take() {
+                Some(enter) => intervals.push(Interval {
+                    enter,
+                    exit: crossing,
+                }),
+                None => enter = Some(crossing),
+            }
+            steps = 0;
+        }
+        if let Some(enter) = enter {
+            intervals.push(Interval {
+                enter,
+                exit: hit(progress.min(end)),
+            });
+        }
+        intervals
+    }
+
+    fn surface_position(&self, position: Vector) -> Vector {
+        let face = self.projection_axis(position);
+        let local = [
+            position.dot_product(self.axes[0]) / self.transform.scale,
+            position.dot_product(self.axes[1]) / self.transform.scale,
+            position.dot_product(self.axes[2]) / self.transform.scale,
+        ];
+
+        Vector {
+            x: local[(face + 1) % 3].rem_euclid(1.0),
+            y: local[(face + 2) % 3].rem_euclid(1.0),
+            z: 0.0,
+        }
+    }
+
+    fn tangent(&self, position: Vector) -> Vector {
+        self.axes[(self.projection_axis(position) + 1) % 3]
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        let mut bounds = self.shape_bounds();
+
+        for child in self.children.iter() {
+            bounds = bounds.union(child.bounding_box());
+        }
+        bounds
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.transform
+    }
+
+    fn move_obj(&mut self, offset: Transform) {
+        self.transform = self.transform + offset;
+        for child in self.children.iter_mut() {
+            child.move_obj(offset);
+        }
+        self.apply_transform();
+    }
+
+    fn set_transform(&mut self, new: Transform) {
+        self.transform = new;
+        self.apply_transform();
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_type(&self) -> String {
+        self.obj_type.clone()
+    }
+
+    fn get_texture(&self) -> Texture {
+        self.texture.clone()
+    }
+
+    fn set_texture(&mut self, new: Texture) {
+        self.texture = new
+    }
+
+    fn get_normal_map(&self) -> Texture {
+        self.normal_map.clone()
+    }
+
+    fn set_normal_map(&mut self, new: Texture) {
+        self.normal_map = new
+    }
+}
@@ -1,9 +1,9 @@
 use crate::vector::{resolve_quadratic_equation, Vector};
 use serde::Serialize;
 
-use crate::renderer::types::{Texture, Transform};
+use crate::renderer::types::{BoundingBox, Texture, Transform};
 
-use super::{Intersection, Object};
+use super::{Intersection, Interval, Object, RayHit};
 
 #[derive(Serialize)]
 pub struct Sphere {
@@ -60,12 +60,40 @@ impl Object for Sphere {
                     intersection_point: point,
                     object: Some(self),
                     light: None,
+                    footprint: 0.0,
+                    primitive: None,
                 });
             }
         }
         found_intersection
     }
 
+    fn intervals(&self, ray: Vector, origin: Vector) -> Vec<Interval<'_>> {
+        let diff = origin - self.transform.pos;
+        let result = resolve_quadratic_equation(
+            ray.dot_product(ray),
+            2.0 * (ray.dot_product(diff)),
+            (diff.dot_product(diff)) - self.radius_applied.powi(2),
+        );
+
+        if result.len() != 2 {
+            return Vec::new();
+        }
+        let hit = |progress: f64| {
+            RayHit::new(
+                self,
+                ray,
+                origin,
+                progress,
+                origin + ray * progress - self.transform.pos,
+            )
+        };
+        vec![Interval {
+            enter: hit(result[0].min(result[1])),
+            exit: hit(result[0].max(result[1])),
+        }]
+    }
+
     fn surface_position(&self, position: Vector) -> Vector {
         let mut rotated_position = position.normalize();
 
@@ -93,6 +121,37 @@ impl Object for Sphere {
         }
     }
 
+    fn tangent(&self, position: Vector) -> Vector {
+        let mut axis = Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 1.0,
+        };
+
+        axis.rotate(
+            self.transform.rotation.x,
+            self.transform.rotation.y,
+            self.transform.rotation.z,
+        );
+        axis.cross_product(position)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        let radius = Vector {
+            x: self.radius_applied,
+            y: self.radius_applied,
+            z: self.radius_applied,
+        };
+        let mut bounds = BoundingBox {
+            min: self.transform.pos - radius,
+            max: self.transform.pos + radius,
+        };
+        for child in self.children.iter() {
+            bounds = bounds.union(child.bounding_box());
+        }
+        bounds
+    }
+
     fn get_transform(&self) -> Transform {
         self.transform
     }
@@ -0,0 +1,237 @@
+use crate::vector::{resolve_quartic_equation, Vector};
+use serde::Serialize;
+
+use crate::renderer::types::{BoundingBox, Texture, Transform};
+
+use super::{Intersection, Interval, Object, RayHit};
+
+/// Roots closer than this share of the tube radius are the same crossing
+const ROOT_EPSILON: f64 = 1e-6;
+
+/// Under this cosine between the ray and the normal the ray only grazes
+/// the surface without going in or out
+const GRAZING_COSINE: f64 = 1e-6;
+
+#[derive(Serialize)]
+pub struct Torus {
+    pub name: String,
+    pub obj_type: String,
+    pub transform: Transform,
+    pub texture: Texture,
+    pub normal_map: Texture,
+    pub major_radius: f64,
+    pub minor_radius: f64,
+    pub(crate) axis: Vector,
+    pub(crate) major_radius_applied: f64,
+    pub(crate) minor_radius_applied: f64,
+    pub children: Vec<Box<dyn Object + Send + Sync>>,
+}
+
+impl Torus {
+    fn local_axis(&self, x: f64, y: f64, z: f64) -> Vector {
+        let mut axis = Vector { x, y, z };
+
+        axis.rotate(
+            self.transform.rotation.x,
+            self.transform.rotation.y,
+            self.transform.rotation.z,
+        );
+        axis
+    }
+
+    fn normal(&self, point: Vector) -> Vector {
+        let local = point - self.transform.pos;
+        let radial = local - self.axis * local.dot_product(self.axis);
+
+        local - radial.normalize() * self.major_radius_applied
+    }
+
+    /// Distances along the normalized ray where it crosses the surface
+    fn roots(&self, direction: Vector, origin: Vector) -> Vec<f64> {
+        let start = origin - self.transform.pos;
+        let major_square = self.major_radius_applied.powi(2);
+
+        // (|p|^2 + R^2 - r^2)^2 = 4 R^2 (|p|^2 - (p.axis)^2) with p = start + t * direction
+        let start_dot_direction = start.dot_product(direction);
+        let start_on_axis = start.dot_product(self.axis);
+        let direction_on_axis = direction.dot_product(self.axis);
+        let sum = start.len2() + major_square - self.minor_radius_applied.powi(2);
+        resolve_quartic_equation(
+            1.0,
+            4.0 * start_dot_direction,
+            4.0 * start_dot_direction.powi(2) + 2.0 * sum
+                - 4.0 * major_square * (1.0 - direction_on_axis.powi(2)),
+            4.0 * start_dot_direction * sum
+                - 8.0 * major_square * (start_dot_direction - start_on_axis * direction_on_axis),
+            sum.powi(2) - 4.0 * major_square * (start.len2() - start_on_axis.powi(2)),
+        )
+    }
+}
+
+impl Object for Torus {
+    fn apply_transform(&mut self) {
+        self.axis = self.local_axis(0.0, 0.0, 1.0);
+        self.major_radius_applied = self.major_radius * self.transform.scale;
+        self.minor_radius_applied = self.minor_radius * self.transform.scale;
+    }
+
+    fn intersection(&self, ray: Vector, origin: Vector) -> Option<Intersection<'_>> {
+        let mut found_intersection: Option<Intersection> = None;
+        let mut smallest_distance: f64 = f64::INFINITY;
+
+        for object in self.children.iter() {
+            if let Some(inters) = object.intersection(ray, origin) {
+                let distance_found = (inters.intersection_point - origin).len();
+                if distance_found < smallest_distance {
+                    smallest_distance = distance_found;
+                    found_intersection = Some(inters);
+                }
+            }
+        }
+        let direction = ray.normalize();
+        let result = self.roots(direction, origin);
+
+        let smallest_result: Option<&f64> = result
+            .iter()
+            .filter(|number| **number > 0.0)
+            .min_by(|a, b| a.partial_cmp(b).unwrap());
+
+        if let Some(smallest) = smallest_result {
+            let intersection_point = origin + direction * *smallest;
+            let normal = self.normal(intersection_point);
+
+            if (intersection_point - origin).len() < smallest_distance {
+                return Some(Intersection {
+                    intersection_point,
+                    normal,
+                    object: Some(self),
+                    light: None,
+                    footprint: 0.0,
+                    primitive: None,
+                });
+            }
+        }
+        found_intersection
+    }
+
+    fn intervals(&self, ray: Vector, origin: Vector) -> Vec<Interval<'_>> {
+        let direction = ray.normalize();
+        let ray_len = ray.len();
+        let mut roots = self.roots(direction, origin);
+        let hit = |distance: f64| {
+            let progress = distance / ray_len;
+            RayHit::new(
+                self,
+                ray,
+                origin,
+                progress,
+                self.normal(origin + ray * progress),
+            )
+        };
+
+        // a grazing ray gives a double root, it counts once and only if the
+        // ray actually goes in or out there
+        roots.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        roots.dedup_by(|b, a| (*b - *a).abs() < ROOT_EPSILON * self.minor_radius_applied);
+        let mut result = Vec::new();
+        let mut enter: Option<f64> = None;
+        for root in roots {
+            let facing = self
+                .normal(origin + direction * root)
+                .normalize()
+                .dot_product(direction);
+            if facing < -GRAZING_COSINE && enter.is_none() {
+                enter = Some(root);
+            } else if facing > GRAZING_COSINE {
+                if let Some(start) = enter.take() {
+                    result.push(Interval {
+                        enter: hit(start),
+                        exit: hit(root),
+                    });
+                }
+            }
+        }
+        result
+    }
+
+    fn surface_position(&self, position: Vector) -> Vector {
+        let height = position.dot_product(self.axis);
+        let radial = (position - self.axis * height).normalize();
+        let tube = position - radial * self.major_radius_applied;
+
+        Vector {
+            x: radial
+                .dot_product(self.local_axis(0.0, 1.0, 0.0))
+                .atan2(radial.dot_product(self.local_axis(1.0, 0.0, 0.0)))
+                / (2.0 * std::f64::consts::PI)
+                + 0.5,
+            y: tube.dot_product(self.axis).atan2(tube.dot_product(radial))
+                / (2.0 * std::f64::consts::PI)
+                + 0.5,
+            z: 0.0,
+        }
+    }
+
+    fn tangent(&self, position: Vector) -> Vector {
+        self.axis.cross_product(position)
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        let outer_radius = self.major_radius_applied + self.minor_radius_applied;
+        let mut bounds = BoundingBox::from_disk(
+            self.transform.pos + self.axis * self.minor_radius_applied,
+            self.axis,
+            outer_radius,
+        )
+        .union(BoundingBox::from_disk(
+            self.transform.pos - self.axis * self.minor_radius_applied,
+            self.axis,
+            outer_radius,
+        ));
+        for child in self.children.iter() {
+            bounds = bounds.union(child.bounding_box());
+        }
+        bounds
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.transform
+    }
+
+    fn move_obj(&mut self, offset: Transform) {
+        self.transform = self.transform + offset;
+        for child in self.children.iter_mut() {
+            child.move_obj(offset);
+        }
+        self.apply_transform();
+    }
+
+    fn set_transform(&mut self, new: Transform) {
+        self.transform = new;
+        self.apply_transform();
+    }
+
+    fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_type(&self) -> String {
+        self.obj_type.clone()
+    }
+
+    fn get_texture(&self) -> Texture {
+        self.texture.clone()
+    }
+
+    fn set_texture(&mut self, new: Texture) {
+        self.texture = new
+    }
+
+    fn get_normal_map(&self) -> Texture {
+        self.normal_map.clone()
+    }
+
+    fn set_normal_map(&mut self, new: Texture) {
+        self.normal_map = new
+    }
+}
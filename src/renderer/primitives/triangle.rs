@@ -1,9 +1,9 @@
 use crate::vector::Vector;
 use serde::Serialize;
 
-use crate::renderer::types::{Texture, Transform};
+use crate::renderer::types::{BoundingBox, Texture, Transform};
 
-use super::{Intersection, Object};
+use super::{Intersection, Interval, Object};
 
 #[derive(Serialize)]
 pub struct Triangle {
@@ -19,9 +19,84 @@ pub struct Triangle {
     pub(crate) point_b_applied: Vector,
     pub(crate) point_c_applied: Vector,
     pub(crate) normal: Vector,
+    pub vertex_normals: Option<[Vector; 3]>,
+    pub vertex_uvs: Option<[Vector; 3]>,
+    pub(crate) vertex_normals_applied: [Vector; 3],
     pub children: Vec<Box<dyn Object + Send + Sync>>,
 }
 
+impl Triangle {
+    /// Weights of the three corners for a point lying on the triangle
+    pub fn barycentric(&self, point: Vector) -> [f64; 3] {
+        let edge_b = self.point_b_applied - self.point_a_applied;
+        let edge_c = self.point_c_applied - self.point_a_applied;
+        let area = edge_b.cross_product(edge_c).dot_product(self.normal);
+        if area == 0.0 {
+            return [1.0, 0.0, 0.0];
+        }
+        let weight_b = (point - self.point_a_applied)
+            .cross_product(edge_c)
+            .dot_product(self.normal)
+            / area;
+        let weight_c = edge_b
+            .cross_product(point - self.point_a_applied)
+            .dot_product(self.normal)
+            / area;
+
+        [1.0 - weight_b - weight_c, weight_b, weight_c]
+    }
+
+    /// Untransformed corners given as (position, normal, uv)
+    pub fn corners(&self) -> [(Vector, Option<Vector>, Option<Vector>); 3] {
+        let points = [self.point_a, self.point_b, self.point_c];
+
+        [0, 1, 2].map(|i| {
+            (
+                points[i],
+                self.vertex_normals.map(|normals| normals[i]),
+                self.vertex_uvs.map(|uvs| uvs[i]),
+            )
+        })
+    }
+
+    /// Progress along the ray where its line crosses the triangle, in front
+    /// of the origin or behind it
+    pub fn crossing(&self, ray: Vector, origin: Vector) -> Option<f64> {
+        let edge_b = self.point_b_applied - self.point_a_applied;
+        let edge_c = self.point_c_applied - self.point_a_applied;
+        let side = ray.cross_product(edge_c);
+        let determinant = edge_b.dot_product(side);
+        if determinant == 0.0 {
+            return None;
+        }
+        let start = origin - self.point_a_applied;
+        let weight_b = start.dot_product(side) / determinant;
+        if !(0.0..=1.0).contains(&weight_b) {
+            return None;
+        }
+        let other_side = start.cross_product(edge_b);
+        let weight_c = ray.dot_product(other_side) / determinant;
+        if weight_c < 0.0 || weight_b + weight_c > 1.0 {
+            return None;
+        }
+        Some(edge_c.dot_product(other_side) / determinant)
+    }
+
+    /// Normal at a point of the triangle, interpolated between the vertex
+    /// normals when it has some
+    pub fn shading_normal(&self, point: Vector) -> Vector {
+        if self.vertex_normals.is_some() {
+            Triangle::interpolate(self.vertex_normals_applied, self.barycentric(point)).normalize()
+        } else {
+            self.normal
+        }
+    }
+
+    fn interpolate(values: [Vector; 3], weights: [f64; 3]) -> Vector {
+        values[0] * weights[0] + values[1] * weights[1] + values[2] * weights[2]
+    }
+}
+
 impl Object for Triangle {
     fn apply_transform(&mut self) {
         self.point_a_applied = self.point_a;
@@ -48,6 +123,16 @@ impl Object for Triangle {
         self.normal = (self.point_b_applied - self.point_a_applied)
             .cross_product(self.point_c_applied - self.point_a_applied)
             .normalize();
+        if let Some(normals) = self.vertex_normals {
+            for (applied, normal) in self.vertex_normals_applied.iter_mut().zip(normals) {
+                *applied = normal;
+                applied.rotate(
+                    self.transform.rotation.x,
+                    self.transform.rotation.y,
+                    self.transform.rotation.z,
+                );
+            }
+        }
     }
 
     fn intersection(&self, ray: Vector, origin: Vector) -> Option<Intersection> {
@@ -103,15 +188,24 @@ impl Object for Triangle {
         if (intersection_point - origin).len() < smallest_distance {
             found_intersection = Some(Intersection {
                 intersection_point,
-                normal: self.normal,
+                normal: self.shading_normal(intersection_point),
                 object: Some(self),
                 light: None,
+                footprint: 0.0,
+                primitive: None,
             })
         }
         found_intersection
     }
 
+    fn intervals(&self, _ray: Vector, _origin: Vector) -> Vec<Interval<'_>> {
+        Vec::new()
+    }
+
     fn surface_position(&self, position: Vector) -> Vector {
+        if let Some(uvs) = self.vertex_uvs {
+            return Triangle::interpolate(uvs, self.barycentric(position + self.transform.pos));
+        }
         let mut rotated_position = position.normalize();
 
         rotated_position.rotate(
@@ -138,6 +232,33 @@ impl Object for Triangle {
         }
     }
 
+    fn tangent(&self, _position: Vector) -> Vector {
+        let edge_b = self.point_b_applied - self.point_a_applied;
+        let edge_c = self.point_c_applied - self.point_a_applied;
+
+        if let Some(uvs) = self.vertex_uvs {
+            let delta_b = uvs[1] - uvs[0];
+            let delta_c = uvs[2] - uvs[0];
+            let determinant = delta_b.x * delta_c.y - delta_c.x * delta_b.y;
+            if determinant != 0.0 {
+                return (edge_b * delta_c.y - edge_c * delta_b.y) / determinant;
+            }
+        }
+        edge_b
+    }
+
+    fn bounding_box(&self) -> BoundingBox {
+        let mut bounds = BoundingBox::from_points(&[
+            self.point_a_applied,
+            self.point_b_applied,
+            self.point_c_applied,
+        ]);
+        for child in self.children.iter() {
+            bounds = bounds.union(child.bounding_box());
+        }
+        bounds
+    }
+
     fn get_transform(&self) -> Transform {
         self.transform
     }
@@ -0,0 +1,190 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// sky
+//
+
+use super::environment::Environment;
+use super::lights::Directional;
+use super::types::{Color, Transform};
+use crate::vector::Vector;
+use std::f64::consts::{FRAC_PI_2, PI};
+
+/// Angular radius of the sun disk, a bit wider than the real one so that
+/// it stays visible at usual resolutions
+const SUN_RADIUS: f64 = 0.01;
+
+/// Brightness of the sun disk compared to its light on a white surface
+const SUN_DISK_STRENGTH: f64 = 40.0;
+
+/// Brings the luminance of the zenith to a value suited to the renderer
+const SKY_EXPOSURE: f64 = 0.35;
+
+/// Clear sky after Preetham, Shirley and Smits, "A Practical Analytic Model
+/// for Daylight"
+pub struct Sky {
+    /// Unit direction pointing at the sun
+    pub sun: Vector,
+    pub turbidity: f64,
+    pub intensity: f64,
+    /// Luminance and chromaticity of the zenith
+    zenith: [f64; 3],
+    /// Perez distribution coefficients of the luminance and chromaticity
+    coefficients: [[f64; 5]; 3],
+}
+
+fn perez(coefficients: &[f64; 5], cos_theta: f64, gamma: f64) -> f64 {
+    (1.0 + coefficients[0] * (coefficients[1] / cos_theta).exp())
+        * (1.0
+            + coefficients[2] * (coefficients[3] * gamma).exp()
+            + coefficients[4] * gamma.cos().powi(2))
+}
+
+impl Sky {
+    /// Elevation and azimuth in degrees, the azimuth turns from straight
+    /// ahead of an unrotated camera toward its right
+    pub fn new(elevation: f64, azimuth: f64, turbidity: f64, intensity: f64) -> Sky {
+        let (elevation, azimuth) = (elevation.to_radians(), azimuth.to_radians());
+        let t = turbidity.clamp(1.7, 10.0);
+        let theta = FRAC_PI_2 - elevation.clamp(0.0, FRAC_PI_2);
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * theta);
+        let polynomial = |a: [f64; 4], b: [f64; 4], c: [f64; 4]| {
+            let powers = [theta.powi(3), theta.powi(2), theta, 1.0];
+            let dot = |row: [f64; 4]| row.iter().zip(powers).map(|(k, p)| k * p).sum::<f64>();
+            t * t * dot(a) + t * dot(b) + dot(c)
+        };
+
+        Sky {
+            sun: Vector {
+                x: elevation.cos() * azimuth.sin(),
+                y: elevation.cos() * azimuth.cos(),
+                z: elevation.sin(),
+            },
+            turbidity: t,
+            intensity,
+            zenith: [
+                (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192,
+                polynomial(
+                    [0.00166, -0.00375, 0.00209, 0.0],
+                    [-0.02903, 0.06377, -0.03202, 0.00394],
+                    [0.11693, -0.21196, 0.06052, 0.25886],
+                ),
+                polynomial(
+                    [0.00275, -0.00610, 0.00317, 0.0],
+                    [-0.04214, 0.08970, -0.04153, 0.00516],
+                    [0.15346, -0.26756, 0.06670, 0.26688],
+                ),
+            ],
+            coefficients: [
+                [
+                    0.1787 * t - 1.4630,
+                    -0.3554 * t + 0.4275,
+                    -0.0227 * t + 5.3251,
+                    0.1206 * t - 2.5771,
+                    -0.0670 * t + 0.3703,
+                ],
+                [
+                    -0.0193 * t - 0.2592,
+                    -0.0665 * t + 0.0008,
+                    -0.0004 * t + 0.2125,
+                    -0.0641 * t - 0.8989,
+                    -0.0033 * t + 0.0452,
+                ],
+                [
+                    -0.0167 * t - 0.2608,
+                    -0.0950 * t + 0.0092,
+                    -0.0079 * t + 0.2102,
+                    -0.0441 * t - 1.6537,
+                    -0.0109 * t + 0.0529,
+                ],
+            ],
+        }
+    }
+
+    /// Sunlight left after crossing the atmosphere, Rayleigh and aerosol
+    /// scattering are taken at the middle of the red, green and blue bands
+    pub fn sun_color(&self) -> Vector {
+        let theta = self.sun.z.clamp(-1.0, 1.0).acos();
+        if theta >= FRAC_PI_2 {
+            return Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            };
+        }
+        let air_mass = 1.0 / (theta.cos() + 0.15 * (93.885 - theta.to_degrees()).powf(-1.253));
+        let beta = 0.04608 * self.turbidity - 0.04586;
+        let transmittance = |wavelength: f64| {
+            (-0.008735 * wavelength.powf(-4.08) * air_mass).exp()
+                * (-beta * wavelength.powf(-1.3) * air_mass).exp()
+        };
+
+        Vector {
+            x: transmittance(0.65),
+            y: transmittance(0.57),
+            z: transmittance(0.475),
+        } * self.intensity
+    }
+
+    /// Directional light standing for the sun of this sky
+    pub fn sun_light(&self) -> Directional {
+        let color = self.sun_color();
+
+        Directional {
+            transform: Transform {
+                pos: self.sun,
+                ..Transform::default()
+            },
+            color: Color {
+                r: color.x * 255.0,
+                g: color.y * 255.0,
+                b: color.z * 255.0,
+            },
+            strength: 1.0,
+            visible: false,
+        }
+    }
+
+    /// Light scattered by the atmosphere toward `direction`, the ground
+    /// below the horizon takes a dim share of it
+    pub fn scattered(&self, direction: Vector) -> Vector {
+        let direction = direction.normalize();
+        let cos_theta = direction.z.max(0.01);
+        let gamma = direction.dot_product(self.sun).clamp(-1.0, 1.0).acos();
+        let sun_theta = self.sun.z.clamp(0.0, 1.0).acos();
+        let value = |index: usize| {
+            self.zenith[index] * perez(&self.coefficients[index], cos_theta, gamma)
+                / perez(&self.coefficients[index], 1.0, sun_theta)
+        };
+        let luminance = value(0) / self.zenith[0] * SKY_EXPOSURE * self.intensity;
+        let (x, y) = (value(1), value(2));
+        let (cie_x, cie_z) = (x / y * luminance, (1.0 - x - y) / y * luminance);
+        let color = Vector {
+            x: (3.2406 * cie_x - 1.5372 * luminance - 0.4986 * cie_z).max(0.0),
+            y: (-0.9689 * cie_x + 1.8758 * luminance + 0.0415 * cie_z).max(0.0),
+            z: (0.0557 * cie_x - 0.2040 * luminance + 1.0570 * cie_z).max(0.0),
+        };
+
+        if direction.z < 0.0 {
+            color * 0.3
+        } else {
+            color
+        }
+    }
+
+    /// Color of a ray leaving the scene, sun disk included
+    pub fn radiance(&self, direction: Vector) -> Vector {
+        let sky = self.scattered(direction);
+
+        if direction.z >= 0.0 && direction.normalize().dot_product(self.sun) > SUN_RADIUS.cos() {
+            return sky + self.sun_color() * SUN_DISK_STRENGTH;
+        }
+        sky
+    }
+
+    /// Sky without its sun, ready to light the scene as an environment
+    pub fn environment(&self, width: usize, height: usize) -> Environment {
+        Environment::from_radiance(width, height, |direction| self.scattered(direction))
+    }
+}
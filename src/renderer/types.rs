@@ -7,9 +7,12 @@
 
 use crate::vector;
 use nannou::image::io::Reader;
+use nannou::image::{load_from_memory, DynamicImage};
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::ops::{Add, Mul, Sub};
+use std::sync::{Arc, Mutex, OnceLock};
 use vector::Vector;
 
 #[derive(Debug, Clone, Copy)]
@@ -98,6 +101,159 @@ impl Mul<Transform> for Transform {
     }
 }
 
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct BoundingBox {
+    pub min: Vector,
+    pub max: Vector,
+}
+
+impl Default for BoundingBox {
+    fn default() -> BoundingBox {
+        BoundingBox {
+            min: Vector {
+                x: f64::INFINITY,
+                y: f64::INFINITY,
+                z: f64::INFINITY,
+            },
+            max: Vector {
+                x: f64::NEG_INFINITY,
+                y: f64::NEG_INFINITY,
+                z: f64::NEG_INFINITY,
+            },
+        }
+    }
+}
+
+impl BoundingBox {
+    pub fn infinite() -> BoundingBox {
+        BoundingBox {
+            min: Vector {
+                x: f64::NEG_INFINITY,
+                y: f64::NEG_INFINITY,
+                z: f64::NEG_INFINITY,
+            },
+            max: Vector {
+                x: f64::INFINITY,
+                y: f64::INFINITY,
+                z: f64::INFINITY,
+            },
+        }
+    }
+
+    pub fn from_points(points: &[Vector]) -> BoundingBox {
+        let mut result = BoundingBox::default();
+
+        for point in points {
+            result.grow(*point);
+        }
+        result
+    }
+
+    pub fn from_disk(center: Vector, normal: Vector, radius: f64) -> BoundingBox {
+        let normal = normal.normalize();
+        let extent = Vector {
+            x: radius * (1.0 - normal.x * normal.x).max(0.0).sqrt(),
+            y: radius * (1.0 - normal.y * normal.y).max(0.0).sqrt(),
+            z: radius * (1.0 - normal.z * normal.z).max(0.0).sqrt(),
+        };
+        BoundingBox {
+            min: center - extent,
+            max: center + extent,
+        }
+    }
+
+    pub fn grow(&mut self, point: Vector) {
+        self.min = Vector {
+            x: self.min.x.min(point.x),
+            y: self.min.y.min(point.y),
+            z: self.min.z.min(point.z),
+        };
+        self.max = Vector {
+            x: self.max.x.max(point.x),
+            y: self.max.y.max(point.y),
+            z: self.max.z.max(point.z),
+        };
+    }
+
+    pub fn union(&self, other: BoundingBox) -> BoundingBox {
+        let mut result = *self;
+
+        result.grow(other.min);
+        result.grow(other.max);
+        result
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.min.x.is_finite()
+            && self.min.y.is_finite()
+            && self.min.z.is_finite()
+            && self.max.x.is_finite()
+            && self.max.y.is_finite()
+            && self.max.z.is_finite()
+    }
+
+    pub fn center(&self) -> Vector {
+        (self.min + self.max) / 2.0
+    }
+
+    /// Whether the point lies in the box grown by `margin` on every side
+    pub fn contains(&self, point: Vector, margin: f64) -> bool {
+        point.x >= self.min.x - margin
+            && point.y >= self.min.y - margin
+            && point.z >= self.min.z - margin
+            && point.x <= self.max.x + margin
+            && point.y <= self.max.y + margin
+            && point.z <= self.max.z + margin
+    }
+
+    pub fn size(&self) -> Vector {
+        self.max - self.min
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let size = self.size();
+        2.0 * (size.x * size.y + size.y * size.z + size.z * size.x)
+    }
+
+    /// Slab test, returns the ray progress at which the box is entered
+    pub fn intersection(&self, ray: Vector, origin: Vector) -> Option<f64> {
+        let mut t_min = 0.0_f64;
+        let mut t_max = f64::INFINITY;
+
+        for (min, max, dir, start) in [
+            (self.min.x, self.max.x, ray.x, origin.x),
+            (self.min.y, self.max.y, ray.y, origin.y),
+            (self.min.z, self.max.z, ray.z, origin.z),
+        ] {
+            if dir == 0.0 {
+                if start < min || start > max {
+                    return None;
+                }
+                continue;
+            }
+            let inverse = 1.0 / dir;
+            let mut t_near = (min - start) * inverse;
+            let mut t_far = (max - start) * inverse;
+            if t_near > t_far {
+                std::mem::swap(&mut t_near, &mut t_far);
+            }
+            t_min = t_min.max(t_near);
+            t_max = t_max.min(t_far);
+            if t_min > t_max {
+                return None;
+            }
+        }
+        Some(t_min)
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, Copy)]
 pub struct Color {
     pub r: f64,
@@ -105,6 +261,28 @@ pub struct Color {
     pub b: f64,
 }
 
+impl Add<Color> for Color {
+    type Output = Color;
+    fn add(self, other: Color) -> Color {
+        Color {
+            r: self.r + other.r,
+            g: self.g + other.g,
+            b: self.b + other.b,
+        }
+    }
+}
+
+impl Mul<f64> for Color {
+    type Output = Color;
+    fn mul(self, other: f64) -> Color {
+        Color {
+            r: self.r * other,
+            g: self.g * other,
+            b: self.b * other,
+        }
+    }
+}
+
 impl Default for Color {
     fn default() -> Color {
         Color {
@@ -120,7 +298,7 @@ impl Color {
         Color {
             r: 128.0,
             g: 128.0,
-            b: 0.0,
+            b: 255.0,
         }
     }
 
@@ -133,11 +311,85 @@ impl Color {
     }
 }
 
+/// Decoded pixels of an image, each level halves the size of the previous one
+#[derive(Debug)]
+pub struct MipLevel {
+    width: usize,
+    height: usize,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl MipLevel {
+    fn downsample(&self) -> MipLevel {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        let mut pixels: Vec<[u8; 3]> = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = [0_u32; 3];
+                for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                    let source_x = (x * 2 + dx).min(self.width - 1);
+                    let source_y = (y * 2 + dy).min(self.height - 1);
+                    let pixel = self.pixels[source_x + source_y * self.width];
+                    for channel in 0..3 {
+                        sum[channel] += pixel[channel] as u32;
+                    }
+                }
+                pixels.push(sum.map(|channel| (channel / 4) as u8));
+            }
+        }
+        MipLevel {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn texel(&self, x: i64, y: i64, wrap: ImageWrap) -> Color {
+        let pixel = self.pixels
+            [wrap.apply(x, self.width as i64) + wrap.apply(y, self.height as i64) * self.width];
+
+        Color {
+            r: pixel[0] as f64,
+            g: pixel[1] as f64,
+            b: pixel[2] as f64,
+        }
+    }
+
+    fn nearest(&self, x: f64, y: f64, wrap: ImageWrap) -> Color {
+        self.texel(
+            (x * self.width as f64).floor() as i64,
+            (y * self.height as f64).floor() as i64,
+            wrap,
+        )
+    }
+
+    fn bilinear(&self, x: f64, y: f64, wrap: ImageWrap) -> Color {
+        let texel_x = x * self.width as f64 - 0.5;
+        let texel_y = y * self.height as f64 - 0.5;
+        let (left, top) = (texel_x.floor(), texel_y.floor());
+        let (weight_x, weight_y) = (texel_x - left, texel_y - top);
+        let (left, top) = (left as i64, top as i64);
+
+        (self.texel(left, top, wrap) * (1.0 - weight_x)
+            + self.texel(left + 1, top, wrap) * weight_x)
+            * (1.0 - weight_y)
+            + (self.texel(left, top + 1, wrap) * (1.0 - weight_x)
+                + self.texel(left + 1, top + 1, wrap) * weight_x)
+                * weight_y
+    }
+}
+
+static IMAGE_CACHE: OnceLock<Mutex<HashMap<String, Arc<Vec<MipLevel>>>>> = OnceLock::new();
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Image {
     pub file: String,
     pub width: i64,
     pub height: i64,
+    #[serde(skip)]
+    pub(crate) levels: Option<Arc<Vec<MipLevel>>>,
 }
 
 impl Default for Image {
@@ -146,8 +398,160 @@ impl Default for Image {
             file: "missing_image.ppm".to_string(),
             height: 0,
             width: 0,
+            levels: None,
+        }
+    }
+}
+
+impl Image {
+    /// Decodes an image once, every later load of the same file shares its pixels
+    pub fn from_file(filename: &str) -> Image {
+        Image::cached(filename, || {
+            Reader::open(filename).ok().map(|reader| {
+                reader
+                    .with_guessed_format()
+                    .expect("file invalid\n")
+                    .decode()
+                    .expect("file invalid\n")
+            })
+        })
+    }
+
+    /// Decodes an image held in memory, `name` keys it in the cache the way
+    /// a file name does and must be unique to these bytes
+    pub fn from_bytes(name: &str, data: &[u8]) -> Image {
+        Image::cached(name, || load_from_memory(data).ok())
+    }
+
+    /// Pixels of the image named `name`, decoded on the first load only,
+    /// a missing image shows the missing texture
+    fn cached<F>(name: &str, decode: F) -> Image
+    where
+        F: FnOnce() -> Option<DynamicImage>,
+    {
+        let mut cache = IMAGE_CACHE
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .unwrap();
+        let levels = cache
+            .entry(name.to_string())
+            .or_insert_with(|| Arc::new(Image::mip_levels(decode())))
+            .clone();
+
+        Image {
+            height: levels[0].height as i64,
+            width: levels[0].width as i64,
+            file: name.to_string(),
+            levels: Some(levels),
         }
     }
+
+    fn mip_levels(image: Option<DynamicImage>) -> Vec<MipLevel> {
+        let data = image
+            .unwrap_or_else(|| {
+                Reader::open("assets/missing_texture.ppm")
+                    .expect("missing missing texture texture\n")
+                    .with_guessed_format()
+                    .expect("file invalid\n")
+                    .decode()
+                    .expect("file invalid\n")
+            })
+            .to_rgb8();
+        let mut levels = vec![MipLevel {
+            width: data.width() as usize,
+            height: data.height() as usize,
+            pixels: data.pixels().map(|pixel| pixel.0).collect(),
+        }];
+
+        while levels[levels.len() - 1].width > 1 || levels[levels.len() - 1].height > 1 {
+            let next = levels[levels.len() - 1].downsample();
+            levels.push(next);
+        }
+        levels
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum ImageFilter {
+    Nearest,
+    Bilinear,
+    /// Blends the two mip levels closest to the footprint of the pixel
+    Trilinear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum ImageWrap {
+    Repeat,
+    Clamp,
+    Mirror,
+}
+
+impl ImageWrap {
+    /// Brings a texel index back inside an image of the given size
+    fn apply(&self, index: i64, size: i64) -> usize {
+        (match self {
+            ImageWrap::Repeat => index.rem_euclid(size),
+            ImageWrap::Clamp => index.clamp(0, size - 1),
+            ImageWrap::Mirror => {
+                let index = index.rem_euclid(2 * size);
+                if index < size {
+                    index
+                } else {
+                    2 * size - 1 - index
+                }
+            }
+        }) as usize
+    }
+}
+
+/// Lighting model used to shade a material
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Shading {
+    Phong,
+    /// GGX microfacets with Smith masking and Schlick Fresnel
+    Pbr,
+}
+
+/// How an image is laid on the texture coordinates of a surface
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct Sampler {
+    pub filter: ImageFilter,
+    pub wrap: ImageWrap,
+    pub offset: Vector,
+    /// Rotation of the image around the texture origin in degrees
+    pub rotation: f64,
+    pub scale: Vector,
+}
+
+impl Default for Sampler {
+    fn default() -> Sampler {
+        Sampler {
+            filter: ImageFilter::Bilinear,
+            wrap: ImageWrap::Repeat,
+            offset: Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            rotation: 0.0,
+            scale: Vector {
+                x: 1.0,
+                y: 1.0,
+                z: 0.0,
+            },
+        }
+    }
+}
+
+impl Sampler {
+    fn transform(&self, u: f64, v: f64) -> (f64, f64) {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+
+        (
+            (u * cos - v * sin) * self.scale.x + self.offset.x,
+            (u * sin + v * cos) * self.scale.y + self.offset.y,
+        )
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -166,8 +570,20 @@ pub struct Texture {
     pub roughness: f64,
     pub transmission: f64,
     pub ior: f64,
+    /// Color left to a ray after crossing `absorption_distance` units of the
+    /// material, white for a clear medium
+    pub absorption: Color,
+    pub absorption_distance: f64,
+    /// Light given off by the surface, scaled by `emission_strength`
+    pub emission: Color,
+    pub emission_strength: f64,
     pub sampling_ponderation: f64,
     pub alpha: f64,
+    pub sampler: Sampler,
+    /// Relief strength when a normal map is read as a height map, zero for
+    /// tangent space normals
+    pub bump: f64,
+    pub shading: Shading,
 }
 
 impl Default for Texture {
@@ -187,8 +603,15 @@ impl Default for Texture {
             roughness: 0.25,
             transmission: 0.0,
             ior: 1.45,
+            absorption: Color::default(),
+            absorption_distance: 1.0,
+            emission: Color::default(),
+            emission_strength: 0.0,
             sampling_ponderation: 1.0,
             alpha: 1.0,
+            sampler: Sampler::default(),
+            bump: 0.0,
+            shading: Shading::Phong,
         }
     }
 }
@@ -220,6 +643,13 @@ impl Texture {
             alpha: 255.0,
             transmission: 0.0,
             ior: 1.45,
+            absorption: Color::default(),
+            absorption_distance: 1.0,
+            emission: Color::default(),
+            emission_strength: 0.0,
+            sampler: Sampler::default(),
+            bump: 0.0,
+            shading: Shading::Phong,
         }
     }
 
@@ -307,38 +737,54 @@ impl Texture {
         }
     }
 
-    fn image_color(&self, u: f64, v: f64) -> Color {
-        let img_x = (((1.0 - u) * self.mod1 % 1.0) * self.image.width as f64) as usize;
-        let img_y = (((1.0 - v) * self.mod2 % 1.0) * self.image.height as f64) as usize;
-        let mut reader = Reader::open(&self.image.file)
-            .unwrap_or(
-                Reader::open("assets/missing_texture.ppm").expect("missing missing texture texture\n"),
-            )
-            .decode()
-            .expect("file invalid\n");
-        if let Some(data) = reader
-            .as_mut_rgb8()
-            .expect("file invalid")
-            .pixels()
-            .nth(img_x + img_y * self.image.width as usize)
-        {
-            Color {
-                r: data.0[0] as f64,
-                g: data.0[1] as f64,
-                b: data.0[2] as f64,
+    fn image_color(&self, u: f64, v: f64, footprint: f64) -> Color {
+        let levels = match &self.image.levels {
+            Some(levels) => levels,
+            None => return Color::default(),
+        };
+        let (x, y) = self.sampler.transform(u, v);
+        // u runs from the right edge of images, and their first row is their top
+        let (x, y) = (1.0 - x, 1.0 - y);
+        let wrap = self.sampler.wrap;
+
+        match self.sampler.filter {
+            ImageFilter::Nearest => levels[0].nearest(x, y, wrap),
+            ImageFilter::Bilinear => levels[0].bilinear(x, y, wrap),
+            ImageFilter::Trilinear => {
+                let texels = footprint
+                    * self.sampler.scale.x.abs().max(self.sampler.scale.y.abs())
+                    * self.image.width.max(self.image.height) as f64;
+                let level = texels.max(1.0).log2().min((levels.len() - 1) as f64);
+                let lower = level.floor() as usize;
+                let upper = (lower + 1).min(levels.len() - 1);
+                let weight = level - lower as f64;
+
+                levels[lower].bilinear(x, y, wrap) * (1.0 - weight)
+                    + levels[upper].bilinear(x, y, wrap) * weight
             }
-        } else {
-            Color::default()
         }
     }
 
+    /// Brightness of the texture read as a height between 0 and 1
+    pub fn height(&self, x: f64, y: f64) -> f64 {
+        let color = self.texture(x, y);
+
+        (color.r + color.g + color.b) / (3.0 * 255.0)
+    }
+
     pub fn texture(&self, x: f64, y: f64) -> Color {
+        self.sample(x, y, 0.0)
+    }
+
+    /// Color at texture coordinates, `footprint` is the size of the pixel seen
+    /// on the surface in texture space and picks the mip level of images
+    pub fn sample(&self, x: f64, y: f64, footprint: f64) -> Color {
         match TexturesTypes::from_u64(self.texture_type) {
             TexturesTypes::Color => self.color,
             TexturesTypes::Gradient => self.gradient_color(x, y),
             TexturesTypes::Perlin => self.perlin_noise(x, y),
             TexturesTypes::Checkers => self.checkers_color(x, y),
-            TexturesTypes::Image => self.image_color(x, y),
+            TexturesTypes::Image => self.image_color(x, y, footprint),
         }
     }
 }
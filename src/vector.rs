@@ -58,6 +58,17 @@ impl Sub<Vector> for Vector {
     }
 }
 
+impl Sub<f64> for Vector {
+    type Output = Vector;
+    fn sub(self, other: f64) -> Vector {
+        Vector {
+            x: self.x - other,
+            y: self.y - other,
+            z: self.z - other,
+        }
+    }
+}
+
 impl Mul<f64> for Vector {
     type Output = Vector;
     fn mul(self, other: f64) -> Vector {
@@ -181,3 +192,100 @@ pub fn resolve_quadratic_equation(a: f64, b: f64, c: f64) -> Vec<f64> {
     }
     results
 }
+
+pub fn resolve_cubic_equation(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    if a == 0.0 {
+        return resolve_quadratic_equation(b, c, d);
+    }
+    let b = b / a;
+    let c = c / a;
+    let d = d / a;
+    // depressed cubic t^3 + p t + q = 0 with x = t - b / 3
+    let p = c - b * b / 3.0;
+    let q = 2.0 * b.powi(3) / 27.0 - b * c / 3.0 + d;
+    let offset = -b / 3.0;
+    let delta = (q / 2.0).powi(2) + (p / 3.0).powi(3);
+    let mut results: Vec<f64> = Vec::new();
+
+    if p.abs() < 1e-12 && q.abs() < 1e-12 {
+        results.push(offset);
+    } else if delta > 0.0 {
+        let sqrt_delta = delta.sqrt();
+        results.push((-q / 2.0 + sqrt_delta).cbrt() + (-q / 2.0 - sqrt_delta).cbrt() + offset);
+    } else if delta == 0.0 {
+        let u = (-q / 2.0).cbrt();
+        results.push(2.0 * u + offset);
+        results.push(-u + offset);
+    } else {
+        let radius = 2.0 * (-p / 3.0).sqrt();
+        let angle = ((3.0 * q) / (p * radius)).clamp(-1.0, 1.0).acos() / 3.0;
+        for k in 0..3 {
+            results.push(radius * (angle - 2.0 * PI * k as f64 / 3.0).cos() + offset);
+        }
+    }
+    results
+}
+
+fn polish_quartic_root(coefficients: [f64; 5], mut root: f64) -> f64 {
+    let [a, b, c, d, e] = coefficients;
+
+    for _ in 0..3 {
+        let value = (((a * root + b) * root + c) * root + d) * root + e;
+        let derivative = ((4.0 * a * root + 3.0 * b) * root + 2.0 * c) * root + d;
+        if derivative == 0.0 {
+            break;
+        }
+        root -= value / derivative;
+    }
+    root
+}
+
+pub fn resolve_quartic_equation(a: f64, b: f64, c: f64, d: f64, e: f64) -> Vec<f64> {
+    if a == 0.0 {
+        return resolve_cubic_equation(b, c, d, e);
+    }
+    let coefficients = [a, b, c, d, e];
+    let b = b / a;
+    let c = c / a;
+    let d = d / a;
+    let e = e / a;
+    // depressed quartic y^4 + p y^2 + q y + r = 0 with x = y - b / 4
+    let p = c - 3.0 * b * b / 8.0;
+    let q = b.powi(3) / 8.0 - b * c / 2.0 + d;
+    let r = -3.0 * b.powi(4) / 256.0 + b * b * c / 16.0 - b * d / 4.0 + e;
+    let offset = -b / 4.0;
+    let mut depressed_roots: Vec<f64> = Vec::new();
+
+    if q.abs() < 1e-12 {
+        // biquadratic
+        for square in resolve_quadratic_equation(1.0, p, r) {
+            if square >= 0.0 {
+                depressed_roots.push(square.sqrt());
+                depressed_roots.push(-square.sqrt());
+            }
+        }
+    } else {
+        // Ferrari's method with the largest root of the resolvent cubic
+        let resolvent = resolve_cubic_equation(1.0, 2.0 * p, p * p - 4.0 * r, -q * q)
+            .into_iter()
+            .fold(f64::NEG_INFINITY, f64::max);
+        if resolvent <= 0.0 {
+            return Vec::new();
+        }
+        let sqrt_resolvent = resolvent.sqrt();
+        depressed_roots.extend(resolve_quadratic_equation(
+            1.0,
+            sqrt_resolvent,
+            (p + resolvent) / 2.0 - q / (2.0 * sqrt_resolvent),
+        ));
+        depressed_roots.extend(resolve_quadratic_equation(
+            1.0,
+            -sqrt_resolvent,
+            (p + resolvent) / 2.0 + q / (2.0 * sqrt_resolvent),
+        ));
+    }
+    depressed_roots
+        .iter()
+        .map(|root| polish_quartic_root(coefficients, root + offset))
+        .collect()
+}
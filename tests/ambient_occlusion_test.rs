@@ -0,0 +1,95 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// ambient occlusion tests
+//
+
+use raytracer::renderer::parsing::Parser;
+use raytracer::renderer::Renderer;
+use serde_json::json;
+use std::fs;
+use std::process::Command;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Camera at the origin seeing the given primitives, the occlusion
+    /// reaches as far as `distance`
+    fn scene(primitives: serde_json::Value, distance: f64) -> Renderer {
+        Parser {}.get_renderer_from_json(
+            &json!({
+                "camera": {
+                    "fov": 60,
+                    "threads": 1,
+                    "ambient_occlusion_samples": 64,
+                    "ambient_occlusion_distance": distance
+                },
+                "primitives": primitives
+            }),
+            4,
+            4,
+        )
+    }
+
+    #[test]
+    fn test_open_sky() {
+        let renderer = scene(
+            json!([{
+                "type": "plane",
+                "normal": {"x": 0, "y": -1, "z": 0},
+                "transform": {"pos": {"x": 0, "y": 5, "z": 0}}
+            }]),
+            1.0,
+        );
+        let pixels = renderer.render_ambient_occlusion();
+
+        assert_eq!(pixels.len(), 4 * 4 * 3);
+        assert!(pixels.iter().all(|&pixel| pixel == 255), "{:?}", pixels);
+    }
+
+    #[test]
+    fn test_closed_box() {
+        let renderer = scene(
+            json!([{
+                "type": "box",
+                "size": {"x": 4.0, "y": 4.0, "z": 4.0}
+            }]),
+            100.0,
+        );
+        let pixels = renderer.render_ambient_occlusion();
+
+        assert_eq!(pixels.len(), 4 * 4 * 3);
+        assert!(pixels.iter().all(|&pixel| pixel < 8), "{:?}", pixels);
+    }
+
+    #[test]
+    fn test_pass_file_written() {
+        let directory = std::env::temp_dir().join("rustracer_ao_test");
+        fs::create_dir_all(&directory).unwrap();
+        let save_file = directory.join("scene.ppm");
+        let pass = directory.join("scene_ao.ppm");
+        let _ = fs::remove_file(&pass);
+
+        let status = Command::new(env!("CARGO_BIN_EXE_raytracer"))
+            .args([
+                "-j",
+                "tests/fixtures/occlusion.json",
+                "-w",
+                "4",
+                "-h",
+                "4",
+                "-s",
+            ])
+            .arg(&save_file)
+            .status()
+            .unwrap();
+        let content = fs::read(&pass).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert!(status.success());
+        assert!(content.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(content.len(), "P6\n4 4\n255\n".len() + 4 * 4 * 3);
+    }
+}
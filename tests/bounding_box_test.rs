@@ -0,0 +1,146 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// bounding box tests
+//
+
+use raytracer::renderer::types::BoundingBox;
+use raytracer::vector::Vector;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_box() -> BoundingBox {
+        BoundingBox {
+            min: Vector {
+                x: -1.0,
+                y: -1.0,
+                z: -1.0,
+            },
+            max: Vector {
+                x: 1.0,
+                y: 1.0,
+                z: 1.0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_default_is_empty() {
+        let bounds = BoundingBox::default();
+
+        assert!(bounds.is_empty());
+        assert_eq!(bounds.surface_area(), 0.0);
+        assert!(!BoundingBox::infinite().is_finite());
+    }
+
+    #[test]
+    fn test_union() {
+        let other = BoundingBox {
+            min: Vector {
+                x: 2.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            max: Vector {
+                x: 3.0,
+                y: 4.0,
+                z: 0.5,
+            },
+        };
+        let result = unit_box().union(other);
+
+        assert_eq!(
+            result.min,
+            Vector {
+                x: -1.0,
+                y: -1.0,
+                z: -1.0,
+            }
+        );
+        assert_eq!(
+            result.max,
+            Vector {
+                x: 3.0,
+                y: 4.0,
+                z: 1.0,
+            }
+        );
+        assert_eq!(BoundingBox::default().union(other).min, other.min);
+    }
+
+    #[test]
+    fn test_intersection() {
+        let origin = Vector {
+            x: 0.0,
+            y: -5.0,
+            z: 0.0,
+        };
+        let toward = Vector {
+            x: 0.0,
+            y: 1.0,
+            z: 0.0,
+        };
+        let away = Vector {
+            x: 0.0,
+            y: -1.0,
+            z: 0.0,
+        };
+
+        assert_eq!(unit_box().intersection(toward, origin), Some(4.0));
+        assert_eq!(unit_box().intersection(away, origin), None);
+        assert_eq!(
+            unit_box().intersection(
+                toward,
+                Vector {
+                    x: 0.0,
+                    y: 0.0,
+                    z: 0.0,
+                }
+            ),
+            Some(0.0)
+        );
+    }
+
+    #[test]
+    fn test_from_disk() {
+        let bounds = BoundingBox::from_disk(
+            Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 1.0,
+            },
+            2.0,
+        );
+
+        assert_eq!(bounds.min.x, -2.0);
+        assert_eq!(bounds.max.y, 2.0);
+        assert_eq!(bounds.max.z, 0.0);
+    }
+
+    #[test]
+    fn test_contains() {
+        let corner = Vector {
+            x: 1.0,
+            y: -1.0,
+            z: 0.5,
+        };
+        let outside = Vector {
+            x: 1.001,
+            y: 0.0,
+            z: 0.0,
+        };
+
+        assert!(unit_box().contains(corner, 0.0));
+        assert!(!unit_box().contains(outside, 0.0));
+        assert!(unit_box().contains(outside, 0.01));
+        assert!(!BoundingBox::default().contains(corner, 1.0));
+    }
+}
@@ -0,0 +1,134 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// brdf sampling tests
+//
+
+use raytracer::renderer::brdf::{ggx_reflection_pdf, sample_cosine, sample_ggx};
+use raytracer::renderer::path_tracer::power_heuristic;
+use raytracer::vector::Vector;
+use std::f64::consts::PI;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn direction(theta: f64, phi: f64) -> Vector {
+        Vector {
+            x: theta.sin() * phi.cos(),
+            y: theta.sin() * phi.sin(),
+            z: theta.cos(),
+        }
+    }
+
+    fn up() -> Vector {
+        Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 1.0,
+        }
+    }
+
+    /// Integral of the reflection density over the directions of the sphere
+    /// picked by `inside`, with the midpoint rule
+    fn integrate_pdf(view: Vector, alpha: f64, inside: impl Fn(Vector) -> bool) -> f64 {
+        let (rows, columns) = (400, 400);
+        let (d_theta, d_phi) = (PI / rows as f64, 2.0 * PI / columns as f64);
+        let mut total = 0.0;
+
+        for row in 0..rows {
+            let theta = (row as f64 + 0.5) * d_theta;
+            for column in 0..columns {
+                let reflected = direction(theta, (column as f64 + 0.5) * d_phi);
+                if inside(reflected) {
+                    total += ggx_reflection_pdf(up(), view, reflected, alpha)
+                        * theta.sin()
+                        * d_theta
+                        * d_phi;
+                }
+            }
+        }
+        total
+    }
+
+    /// Share of the reflections of `view` about `sample_ggx` normals picked
+    /// by `inside`
+    fn sampled_share(view: Vector, alpha: f64, inside: impl Fn(Vector) -> bool) -> f64 {
+        let count = 40000;
+        let mut hits = 0;
+
+        for _ in 0..count {
+            let half = sample_ggx(up(), alpha);
+            let reflected = half * (2.0 * view.dot_product(half)) - view;
+            if view.dot_product(half) > 0.0 && inside(reflected) {
+                hits += 1;
+            }
+        }
+        hits as f64 / count as f64
+    }
+
+    #[test]
+    fn test_ggx_pdf_normalized() {
+        for alpha in [0.3, 0.6, 1.0] {
+            let total = integrate_pdf(up(), alpha, |_| true);
+
+            assert!((total - 1.0).abs() < 0.01, "alpha {}: {}", alpha, total);
+        }
+    }
+
+    #[test]
+    fn test_ggx_pdf_matches_sampling() {
+        let view = direction(0.6, 0.4);
+        let alpha = 0.5;
+        let regions: [&dyn Fn(Vector) -> bool; 3] = [
+            &|reflected: Vector| reflected.z > 0.5,
+            &|reflected: Vector| reflected.x > 0.0,
+            &|reflected: Vector| reflected.dot_product(direction(0.6, 0.4 + PI)) > 0.9,
+        ];
+
+        for inside in regions {
+            let expected = integrate_pdf(view, alpha, inside);
+            let found = sampled_share(view, alpha, inside);
+
+            assert!(
+                (found - expected).abs() < 0.015,
+                "{} != {}",
+                found,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_power_heuristic_weights_sum() {
+        for (pdf, other) in [(1.0, 1.0), (0.2, 3.0), (5.0, 0.01), (0.0, 2.0)] {
+            let sum = power_heuristic(pdf, other) + power_heuristic(other, pdf);
+
+            assert!((sum - 1.0).abs() < 1e-12, "{} + {}: {}", pdf, other, sum);
+        }
+        assert_eq!(power_heuristic(0.0, 0.0), 0.0);
+        assert!(power_heuristic(3.0, 1.0) > 0.75);
+    }
+
+    #[test]
+    fn test_sample_cosine_hemisphere() {
+        let normals = [up(), direction(1.2, 2.0), direction(PI, 0.0)];
+
+        for normal in normals {
+            let count = 20000;
+            let mut cosine_sum = 0.0;
+
+            for _ in 0..count {
+                let sample = sample_cosine(normal);
+                let cosine = sample.dot_product(normal);
+
+                assert!((sample.len() - 1.0).abs() < 1e-9);
+                assert!(cosine >= 0.0, "{:?} leaves {:?}", sample, normal);
+                cosine_sum += cosine;
+            }
+            // the cosine averages 2/3 under a cosine density
+            assert!((cosine_sum / count as f64 - 2.0 / 3.0).abs() < 0.01);
+        }
+    }
+}
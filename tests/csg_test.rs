@@ -0,0 +1,152 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// csg tests
+//
+
+use raytracer::renderer::parsing::Parser;
+use raytracer::renderer::primitives::csg::{combine_intervals, CsgOperation};
+use raytracer::renderer::primitives::{Interval, Object, RayHit};
+use raytracer::vector::Vector;
+use serde_json::json;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector(x: f64, y: f64, z: f64) -> Vector {
+        Vector { x, y, z }
+    }
+
+    /// Spans along the x axis, each boundary pointing away from the span
+    fn spans<'a>(object: &'a dyn Object, bounds: &[(f64, f64)]) -> Vec<Interval<'a>> {
+        let ray = vector(1.0, 0.0, 0.0);
+        let origin = vector(0.0, 0.0, 0.0);
+
+        bounds
+            .iter()
+            .map(|(enter, exit)| Interval {
+                enter: RayHit::new(object, ray, origin, *enter, ray * -1.0),
+                exit: RayHit::new(object, ray, origin, *exit, ray),
+            })
+            .collect()
+    }
+
+    fn progress(intervals: &[Interval]) -> Vec<(f64, f64)> {
+        intervals
+            .iter()
+            .map(|span| (span.enter.progress, span.exit.progress))
+            .collect()
+    }
+
+    fn combine(
+        operation: CsgOperation,
+        left: &[(f64, f64)],
+        right: &[(f64, f64)],
+    ) -> Vec<(f64, f64)> {
+        let object = Parser {}.get_sphere_from_json(&json!({}));
+
+        progress(&combine_intervals(
+            operation,
+            spans(object.as_ref(), left),
+            spans(object.as_ref(), right),
+        ))
+    }
+
+    #[test]
+    fn test_union() {
+        assert_eq!(
+            combine(CsgOperation::Union, &[(0.0, 2.0)], &[(1.0, 3.0)]),
+            vec![(0.0, 3.0)]
+        );
+        assert_eq!(
+            combine(CsgOperation::Union, &[(0.0, 1.0)], &[(2.0, 3.0)]),
+            vec![(0.0, 1.0), (2.0, 3.0)]
+        );
+        // nested spans
+        assert_eq!(
+            combine(CsgOperation::Union, &[(0.0, 4.0)], &[(1.0, 2.0)]),
+            vec![(0.0, 4.0)]
+        );
+    }
+
+    #[test]
+    fn test_undefined_span() {
+        let spans = combine(CsgOperation::Union, &[(0.0, 2.0)], &[(f64::NAN, f64::NAN)]);
+
+        assert_eq!(spans[0], (0.0, 2.0));
+    }
+
+    #[test]
+    fn test_intersection() {
+        assert_eq!(
+            combine(CsgOperation::Intersection, &[(0.0, 2.0)], &[(1.0, 3.0)]),
+            vec![(1.0, 2.0)]
+        );
+        assert_eq!(
+            combine(CsgOperation::Intersection, &[(0.0, 4.0)], &[(1.0, 2.0)]),
+            vec![(1.0, 2.0)]
+        );
+        assert!(combine(CsgOperation::Intersection, &[(0.0, 1.0)], &[(2.0, 3.0)]).is_empty());
+        // touching spans share no volume
+        assert!(combine(CsgOperation::Intersection, &[(0.0, 1.0)], &[(1.0, 2.0)]).is_empty());
+    }
+
+    #[test]
+    fn test_difference() {
+        let object = Parser {}.get_sphere_from_json(&json!({}));
+        let carved = combine_intervals(
+            CsgOperation::Difference,
+            spans(object.as_ref(), &[(0.0, 4.0)]),
+            spans(object.as_ref(), &[(1.0, 2.0)]),
+        );
+
+        assert_eq!(progress(&carved), vec![(0.0, 1.0), (2.0, 4.0)]);
+        // the walls of the hole face into it
+        assert_eq!(carved[0].exit.intersection.normal, vector(1.0, 0.0, 0.0));
+        assert_eq!(carved[1].enter.intersection.normal, vector(-1.0, 0.0, 0.0));
+        assert_eq!(
+            combine(CsgOperation::Difference, &[(0.0, 2.0)], &[(1.0, 3.0)]),
+            vec![(0.0, 1.0)]
+        );
+        assert!(combine(CsgOperation::Difference, &[(1.0, 2.0)], &[(0.0, 4.0)]).is_empty());
+    }
+
+    #[test]
+    fn test_plane_half_space() {
+        let plane = Parser {}.get_plane_from_json(&json!({
+            "normal": {"x": 0.0, "y": 0.0, "z": 1.0}
+        }));
+        let down = plane.intervals(vector(0.0, 0.0, -1.0), vector(0.0, 0.0, 2.0));
+        let up = plane.intervals(vector(0.0, 0.0, 1.0), vector(0.0, 0.0, 2.0));
+
+        assert_eq!(progress(&down), vec![(2.0, f64::INFINITY)]);
+        assert_eq!(progress(&up), vec![(f64::NEG_INFINITY, -2.0)]);
+        assert!(plane
+            .intervals(vector(1.0, 0.0, 0.0), vector(0.0, 0.0, 2.0))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_mesh_intervals() {
+        let mesh = Parser {}.get_mesh_from_json(&json!({"file": "tests/fixtures/cube.obj"}));
+        // goes through the diagonals splitting the faces in two triangles
+        let through = mesh.intervals(vector(1.0, 0.0, 0.0), vector(-3.0, 0.0, 0.0));
+        let inside = mesh.intervals(vector(0.0, 2.0, 0.0), vector(0.2, 0.0, 0.3));
+
+        assert_eq!(progress(&through), vec![(2.0, 4.0)]);
+        assert_eq!(progress(&inside), vec![(-0.5, 0.5)]);
+    }
+
+    #[test]
+    fn test_triangle_operand_rejected() {
+        let csg = Parser {}.get_csg_from_json(&json!({
+            "operation": "difference",
+            "left": {"type": "sphere"},
+            "right": {"type": "triangle"}
+        }));
+
+        assert!(csg.is_none());
+    }
+}
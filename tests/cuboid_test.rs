@@ -0,0 +1,101 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// cuboid tests
+//
+
+use raytracer::renderer::parsing::Parser;
+use raytracer::renderer::primitives::cuboid::Cuboid;
+use raytracer::renderer::primitives::Object;
+use raytracer::vector::Vector;
+use serde_json::json;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Box of 2 by 4 by 6 centered on the origin
+    fn cuboid() -> Box<Cuboid> {
+        Parser {}.get_cuboid_from_json(&json!({
+            "size": {"x": 2.0, "y": 4.0, "z": 6.0}
+        }))
+    }
+
+    fn vector(x: f64, y: f64, z: f64) -> Vector {
+        Vector { x, y, z }
+    }
+
+    fn assert_close(found: Vector, expected: Vector) {
+        assert!(
+            (found - expected).len() < 1e-9,
+            "{:?} != {:?}",
+            found,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_slab_intersection() {
+        let cuboid = cuboid();
+        let intersect = cuboid
+            .intersection(vector(1.0, 0.0, 0.0), vector(-5.0, 0.5, 0.5))
+            .unwrap();
+        let intervals = cuboid.intervals(vector(2.0, 0.0, 0.0), vector(-5.0, 0.5, 0.5));
+
+        assert_close(intersect.intersection_point, vector(-1.0, 0.5, 0.5));
+        assert_eq!(intervals.len(), 1);
+        assert!((intervals[0].enter.progress - 2.0).abs() < 1e-9);
+        assert!((intervals[0].exit.progress - 3.0).abs() < 1e-9);
+        assert!(cuboid
+            .intersection(vector(1.0, 0.0, 0.0), vector(-5.0, 2.5, 0.0))
+            .is_none());
+    }
+
+    #[test]
+    fn test_face_normals() {
+        let cuboid = cuboid();
+
+        for axis in [
+            vector(1.0, 0.0, 0.0),
+            vector(0.0, 1.0, 0.0),
+            vector(0.0, 0.0, 1.0),
+        ] {
+            for side in [1.0, -1.0] {
+                let outward = axis * side;
+                let intersect = cuboid
+                    .intersection(outward * -1.0, outward * 10.0 + vector(0.1, 0.1, 0.1))
+                    .unwrap();
+                assert_close(intersect.normal.normalize(), outward);
+            }
+        }
+    }
+
+    #[test]
+    fn test_face_uvs() {
+        let cuboid = cuboid();
+
+        // both faces of an axis read the texture from the outside
+        assert_close(
+            cuboid.surface_position(vector(1.0, 1.0, 1.5)),
+            vector(0.75, 0.75, 0.0),
+        );
+        assert_close(
+            cuboid.surface_position(vector(-1.0, 1.0, 1.5)),
+            vector(0.25, 0.75, 0.0),
+        );
+        assert_close(
+            cuboid.surface_position(vector(0.5, 2.0, 1.5)),
+            vector(0.75, 0.75, 0.0),
+        );
+        assert_close(
+            cuboid.surface_position(vector(0.5, -2.0, 1.5)),
+            vector(0.25, 0.75, 0.0),
+        );
+        assert_close(cuboid.tangent(vector(1.0, 1.0, 1.5)), vector(0.0, 1.0, 0.0));
+        assert_close(
+            cuboid.tangent(vector(-1.0, 1.0, 1.5)),
+            vector(0.0, -1.0, 0.0),
+        );
+    }
+}
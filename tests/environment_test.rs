@@ -0,0 +1,68 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// environment tests
+//
+
+use raytracer::renderer::environment::{decode_hdr, Environment};
+use raytracer::vector::Vector;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &[u8] = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n";
+
+    #[test]
+    fn test_decode_flat_scanlines() {
+        let mut data = HEADER.to_vec();
+        data.extend_from_slice(b"-Y 1 +X 2\n");
+        data.extend_from_slice(&[128, 64, 0, 129, 0, 0, 0, 0]);
+        let (width, height, pixels) = decode_hdr(&data).unwrap();
+
+        assert_eq!((width, height), (2, 1));
+        assert_eq!(
+            pixels[0],
+            [1.0 + 0.5 / 128.0, 0.5 + 0.5 / 128.0, 0.5 / 128.0]
+        );
+        assert_eq!(pixels[1], [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_decode_run_length_scanlines() {
+        let mut data = HEADER.to_vec();
+        data.extend_from_slice(b"-Y 1 +X 8\n");
+        data.extend_from_slice(&[2, 2, 0, 8]);
+        // red is one run, green two literals and a run, blue and exponent runs
+        data.extend_from_slice(&[136, 255]);
+        data.extend_from_slice(&[2, 10, 20, 134, 30]);
+        data.extend_from_slice(&[136, 0]);
+        data.extend_from_slice(&[136, 128]);
+        let (_, _, pixels) = decode_hdr(&data).unwrap();
+
+        assert_eq!(pixels.len(), 8);
+        assert_eq!(pixels[1][1], 20.5 / 256.0);
+        assert_eq!(pixels[7][1], 30.5 / 256.0);
+        assert_eq!(pixels[7][0], 255.5 / 256.0);
+    }
+
+    #[test]
+    fn test_sampling_follows_brightness() {
+        let mut pixels = vec![[0.0_f32; 3]; 8 * 4];
+        // a single bright pixel, right of the center row above the horizon
+        pixels[8 + 5] = [100.0, 100.0, 100.0];
+        let environment = Environment::new(8, 4, pixels, 0.0, 1.0);
+        let expected = Vector {
+            x: (std::f64::consts::PI * 0.375).sin() * (std::f64::consts::PI * 0.375).sin(),
+            y: (std::f64::consts::PI * 0.375).sin() * (std::f64::consts::PI * 0.375).cos(),
+            z: (std::f64::consts::PI * 0.375).cos(),
+        };
+
+        for random in [(0.1, 0.2), (0.5, 0.5), (0.9, 0.7)] {
+            let (direction, pdf) = environment.sample(random).unwrap();
+            assert!(direction.dot_product(expected) > 0.8);
+            assert!((pdf - environment.pdf(direction)).abs() < 1e-9 * pdf);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// gltf tests
+//
+
+use raytracer::renderer::parsing::Parser;
+use raytracer::renderer::Renderer;
+use raytracer::vector::Vector;
+use serde_json::{json, Value};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scene() -> Renderer {
+        Parser {}
+            .get_renderer_from_gltf("tests/fixtures/embedded.gltf", 10, 10)
+            .unwrap()
+    }
+
+    fn point_vector(x: f64, y: f64, z: f64) -> Vector {
+        Vector { x, y, z }
+    }
+
+    fn point(x: f64, y: f64, z: f64) -> Value {
+        json!({"x": x, "y": y, "z": z})
+    }
+
+    #[test]
+    fn test_node_hierarchy() {
+        let primitives = serde_json::to_value(&scene().primitives).unwrap();
+        let root = &primitives[0];
+        let names: Vec<&str> = root["children"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|child| child["name"].as_str().unwrap())
+            .collect();
+        let mesh = &root["children"][0]["children"][0];
+
+        assert_eq!(primitives.as_array().unwrap().len(), 1);
+        assert_eq!(root["name"], "root");
+        assert_eq!(names, ["body", "lamp", "spot"]);
+        assert_eq!(mesh["obj_type"], "mesh");
+        assert_eq!(mesh["name"], "triangle");
+        // both translations add up, then Y up turns into Z up
+        assert_eq!(
+            mesh["triangles"][0]["point_a_applied"],
+            point(1.0, 2.0, 0.0)
+        );
+        assert_eq!(
+            mesh["triangles"][0]["point_b_applied"],
+            point(2.0, 2.0, 0.0)
+        );
+        assert_eq!(
+            mesh["triangles"][0]["point_c_applied"],
+            point(1.0, 2.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn test_material() {
+        let primitives = serde_json::to_value(&scene().primitives).unwrap();
+        let texture = &primitives[0]["children"][0]["children"][0]["triangles"][0]["texture"];
+
+        assert_eq!(texture["texture_type"], 4);
+        assert_eq!(texture["shading"], "Pbr");
+        assert_eq!(
+            texture["color"],
+            json!({"r": 255.0, "g": 127.5, "b": 63.75})
+        );
+        assert_eq!(texture["metalness"], 0.25);
+        assert_eq!(texture["roughness"], 0.75);
+        assert_eq!(texture["ior"], 1.25);
+        // the image embedded in the buffer is decoded without touching the disk
+        assert_eq!(
+            texture["image"],
+            json!({"file": "tests/fixtures/embedded.gltf#image0", "width": 2, "height": 1})
+        );
+    }
+
+    #[test]
+    fn test_lights() {
+        let lights = serde_json::to_value(&scene().lights).unwrap();
+        let (lamp, spot) = (&lights["lights"][0], &lights["lights"][1]);
+
+        assert_eq!(lights["lights"].as_array().unwrap().len(), 2);
+        assert_eq!(lamp["transform"]["pos"], point(0.0, 2.0, 3.0));
+        assert_eq!(lamp["color"], json!({"r": 255.0, "g": 127.5, "b": 0.0}));
+        assert_eq!(lamp["strength"], 2.0);
+        assert_eq!(spot["transform"]["pos"], point(0.0, 2.0, 0.0));
+        // spots shine down their local -z axis, which is the renderer +y
+        assert_eq!(spot["transform"]["rotation"]["x"], 90.0);
+        assert_eq!(spot["strength"], 3.0);
+        assert!((spot["inner_angle"].as_f64().unwrap() - 0.25_f64.to_degrees()).abs() < 1e-9);
+        assert!((spot["outer_angle"].as_f64().unwrap() - 0.5_f64.to_degrees()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_texture_coordinates() {
+        let renderer = scene();
+        // the image is a red texel left of a blue one, glTF maps its top left at (0, 0)
+        let color = |x: f64, z: f64| {
+            let hit = renderer.primitives[0]
+                .intersection(point_vector(0.0, 1.0, 0.0), point_vector(x, 0.0, z))
+                .unwrap();
+            let object = hit.object.unwrap();
+            let uv = object.surface_position(hit.intersection_point - object.get_transform().pos);
+            let color = object.get_texture().texture(uv.x, uv.y);
+
+            (color.r, color.g, color.b)
+        };
+
+        assert_eq!(color(1.25, 0.1), (255.0, 0.0, 0.0));
+        assert_eq!(color(1.75, 0.1), (0.0, 0.0, 255.0));
+    }
+}
@@ -0,0 +1,96 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// obj material tests
+//
+
+use raytracer::renderer::parsing::Parser;
+use raytracer::renderer::primitives::mesh::Mesh;
+use raytracer::renderer::types::{Texture, TexturesTypes};
+use serde_json::json;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mesh() -> Box<Mesh> {
+        Parser {}.get_mesh_from_json(&json!({
+            "file": "tests/fixtures/materials.obj",
+            "texture": {
+                "texture_type": 1,
+                "color": {"r": 10, "g": 20, "b": 30},
+                "diffuse": 0.3,
+                "specular": 0.1,
+                "shininess": 4.0
+            }
+        }))
+    }
+
+    fn textures() -> Vec<Texture> {
+        mesh()
+            .triangles
+            .iter()
+            .map(|triangle| triangle.texture.clone())
+            .collect()
+    }
+
+    fn assert_close(found: f64, expected: f64) {
+        assert!((found - expected).abs() < 1e-9, "{} != {}", found, expected);
+    }
+
+    #[test]
+    fn test_groups() {
+        let textures = textures();
+
+        assert_eq!(textures.len(), 6);
+        // faces before any usemtl keep the mesh texture
+        assert_eq!(textures[0].texture_type, TexturesTypes::Gradient as u64);
+        assert_eq!(textures[0].color.r, 10.0);
+        // a new group keeps the current material
+        assert_eq!(textures[1].color.b, 255.0);
+        assert_eq!(textures[2].color.b, 255.0);
+        assert_eq!(textures[2].shininess, 64.0);
+    }
+
+    #[test]
+    fn test_phong_statements() {
+        let shiny = &textures()[1];
+
+        assert_eq!(shiny.texture_type, TexturesTypes::Color as u64);
+        assert_close(shiny.color.r, 63.75);
+        assert_close(shiny.color.g, 127.5);
+        assert_close(shiny.color.b, 255.0);
+        assert_close(shiny.specular, 0.5);
+        assert_close(shiny.shininess, 64.0);
+        assert_close(shiny.alpha, 0.25);
+        assert_close(shiny.transmission, Texture::default().transmission);
+        // what the file leaves out comes from the mesh texture
+        assert_close(shiny.diffuse, 0.3);
+    }
+
+    #[test]
+    fn test_refractive_statements() {
+        let textures = textures();
+        let (glass, clear) = (&textures[3], &textures[4]);
+
+        assert_close(glass.ior, 1.5);
+        assert_close(glass.transmission, 0.75);
+        assert_close(glass.alpha, Texture::default().alpha);
+        // a refractive model without dissolve lets everything through
+        assert_close(clear.transmission, 1.0);
+        assert_close(clear.specular, 0.1);
+    }
+
+    #[test]
+    fn test_diffuse_map() {
+        let painted = &textures()[5];
+
+        assert_eq!(painted.texture_type, TexturesTypes::Image as u64);
+        assert_close(painted.color.g, 127.5);
+        assert_eq!(painted.image.file, "tests/fixtures/checker.ppm");
+        assert_eq!((painted.image.width, painted.image.height), (2, 2));
+        assert_close(painted.sampler.scale.x, 1.0);
+        assert_close(painted.sampler.scale.y, 1.0);
+    }
+}
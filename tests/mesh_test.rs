@@ -0,0 +1,238 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// mesh tests
+//
+
+use raytracer::renderer::parsing::Parser;
+use raytracer::renderer::primitives::mesh::{Mesh, MAX_SUBDIVISIONS};
+use raytracer::renderer::primitives::triangle::Triangle;
+use raytracer::renderer::primitives::Object;
+use raytracer::renderer::types::{Color, Texture};
+use raytracer::vector::Vector;
+use serde_json::json;
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector(x: f64, y: f64, z: f64) -> Vector {
+        Vector { x, y, z }
+    }
+
+    fn faces() -> Box<Mesh> {
+        Parser {}.get_mesh_from_json(&json!({"file": "tests/fixtures/faces.obj"}))
+    }
+
+    fn points(triangle: &Triangle) -> [Vector; 3] {
+        [triangle.point_a, triangle.point_b, triangle.point_c]
+    }
+
+    fn color(triangle: &Triangle) -> (f64, f64, f64) {
+        let Color { r, g, b } = triangle.texture.color;
+        (r, g, b)
+    }
+
+    #[test]
+    fn test_quad_with_uvs_and_normals() {
+        let mesh = faces();
+        let normal = vector(0.0, 0.0, 1.0);
+
+        assert_eq!(mesh.triangles.len(), 6);
+        assert_eq!(
+            points(&mesh.triangles[0]),
+            [
+                vector(0.0, 0.0, 0.0),
+                vector(1.0, 0.0, 0.0),
+                vector(1.0, 1.0, 0.0)
+            ]
+        );
+        assert_eq!(
+            points(&mesh.triangles[1]),
+            [
+                vector(0.0, 0.0, 0.0),
+                vector(1.0, 1.0, 0.0),
+                vector(0.0, 1.0, 0.0)
+            ]
+        );
+        assert_eq!(mesh.triangles[1].vertex_normals, Some([normal; 3]));
+        // u is turned around to run from the right edge of images
+        assert_eq!(
+            mesh.triangles[1].vertex_uvs,
+            Some([
+                vector(1.0, 0.0, 0.0),
+                vector(0.0, 1.0, 0.0),
+                vector(1.0, 1.0, 0.0)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_negative_indices() {
+        let mesh = faces();
+        let triangle = &mesh.triangles[2];
+
+        assert_eq!(
+            points(triangle),
+            [
+                vector(0.0, 0.0, 0.0),
+                vector(1.0, 0.0, 0.0),
+                vector(1.0, 1.0, 0.0)
+            ]
+        );
+        assert!(triangle.vertex_normals.is_some());
+        assert!(triangle.vertex_uvs.is_none());
+    }
+
+    #[test]
+    fn test_polygon_fan() {
+        let mesh = faces();
+        let first = vector(2.0, 0.0, 0.0);
+
+        for (triangle, (b, c)) in mesh.triangles[3..].iter().zip([
+            (vector(3.0, 0.0, 0.0), vector(3.5, 1.0, 0.0)),
+            (vector(3.5, 1.0, 0.0), vector(2.5, 2.0, 0.0)),
+            (vector(2.5, 2.0, 0.0), vector(1.5, 1.0, 0.0)),
+        ]) {
+            assert_eq!(points(triangle), [first, b, c]);
+            assert!(triangle.vertex_normals.is_none());
+        }
+    }
+
+    #[test]
+    fn test_groups_and_materials() {
+        let mesh = faces();
+        let default = Texture::default().color;
+
+        assert_eq!(color(&mesh.triangles[0]), (255.0, 0.0, 0.0));
+        assert_eq!(color(&mesh.triangles[1]), (255.0, 0.0, 0.0));
+        // an unknown material falls back on the mesh texture
+        assert_eq!(color(&mesh.triangles[2]), (default.r, default.g, default.b));
+        for triangle in &mesh.triangles[3..] {
+            assert_eq!(color(triangle), (0.0, 0.0, 255.0));
+        }
+    }
+
+    #[test]
+    fn test_invalid_faces() {
+        let mut mesh = Parser {}.get_mesh_from_json(&json!({}));
+        let verteces = [
+            vector(0.0, 0.0, 0.0),
+            vector(1.0, 0.0, 0.0),
+            vector(0.0, 1.0, 0.0),
+        ];
+        let texture = Texture::default();
+        let mut parse =
+            |line: &str| mesh.parse_face(line.to_string(), &verteces, &[], &[], &texture);
+
+        assert_eq!(parse("f 1 2 -1").map(|faces| faces.len()), Some(1));
+        assert!(parse("f 1 2").is_none());
+        assert!(parse("f 1 2 4").is_none());
+        assert!(parse("f 1 2 -4").is_none());
+        assert!(parse("f 1 2 0").is_none());
+        // indices into empty normal or uv lists
+        assert!(parse("f 1/1 2/1 3/1").is_none());
+        assert!(parse("f 1//1 2//1 3//1").is_none());
+        assert!(parse("f 1/1/1/1 2 3").is_none());
+    }
+
+    #[test]
+    fn test_rotation_applied_once() {
+        let mesh = Parser {}.get_mesh_from_json(&json!({
+            "file": "tests/fixtures/faces.obj",
+            "transform": {
+                "pos": {"x": 0.0, "y": 0.0, "z": 0.0},
+                "rotation": {"x": 0.0, "y": 0.0, "z": 90.0},
+                "scale": 1.0
+            }
+        }));
+        let size = mesh.bounding_box().size();
+
+        // the vertices stay as written in the file
+        assert_eq!(mesh.triangles[0].point_b, vector(1.0, 0.0, 0.0));
+        // a quarter turn swaps the 3.5 by 2 extent of the faces
+        assert!((size.x - 2.0).abs() < 1e-9, "{:?}", size);
+        assert!((size.y - 3.5).abs() < 1e-9, "{:?}", size);
+    }
+
+    #[test]
+    fn test_surface_position_of_hit() {
+        let mesh = Parser {}.get_mesh_from_json(&json!({
+            "file": "tests/fixtures/faces.obj",
+            "transform": {
+                "pos": {"x": 5.0, "y": 0.0, "z": 0.0},
+                "rotation": {"x": 0.0, "y": 0.0, "z": 0.0},
+                "scale": 1.0
+            }
+        }));
+        let hit = mesh
+            .intersection(vector(0.0, 0.0, -1.0), vector(5.25, 0.75, 1.0))
+            .unwrap();
+        let uv = mesh.surface_position(hit.intersection_point - vector(5.0, 0.0, 0.0));
+
+        assert!((uv.x - 0.75).abs() < 1e-9, "{:?}", uv);
+        assert!((uv.y - 0.75).abs() < 1e-9, "{:?}", uv);
+    }
+
+    #[test]
+    fn test_degenerate_face_ignored() {
+        let mesh = Parser {}.get_mesh_from_json(&json!({"file": "tests/fixtures/degenerate.obj"}));
+        let uv = mesh.surface_position(vector(0.25, 0.0, 0.0));
+        let tangent = mesh.tangent(vector(0.25, 0.0, 0.0));
+
+        assert_eq!(mesh.triangles.len(), 2);
+        // the u of 0.25 in the file is turned around on import
+        assert!((uv.x - 0.75).abs() < 1e-9, "{:?}", uv);
+        assert!(uv.y.abs() < 1e-9, "{:?}", uv);
+        assert!(
+            tangent.x.is_finite() && tangent.y.is_finite(),
+            "{:?}",
+            tangent
+        );
+    }
+
+    #[test]
+    fn test_displacement_stays_closed() {
+        let mesh = Parser {}.get_mesh_from_json(&json!({
+            "file": "tests/fixtures/cube.obj",
+            "displacement": {
+                "texture": {"texture_type": 2, "mod1": 4.0, "mod2": 4.0},
+                "scale": 0.3,
+                "subdivisions": 2
+            }
+        }));
+        let key = |point: Vector| (point.x.to_bits(), point.y.to_bits(), point.z.to_bits());
+        let mut edges: HashMap<_, usize> = HashMap::new();
+
+        assert_eq!(mesh.triangles.len(), 12 * 16);
+        for triangle in &mesh.triangles {
+            let [a, b, c] = points(triangle).map(key);
+            for (start, end) in [(a, b), (b, c), (c, a)] {
+                *edges.entry((start.min(end), start.max(end))).or_default() += 1;
+            }
+        }
+        // every edge still joins exactly two faces
+        assert!(edges.values().all(|count| *count == 2));
+        // and the relief did push vertices off the faces of the cube
+        assert!(mesh.triangles.iter().any(|triangle| {
+            points(triangle).iter().any(|point| {
+                (point.x.abs().max(point.y.abs()).max(point.z.abs()) - 1.0).abs() > 0.01
+            })
+        }));
+    }
+
+    #[test]
+    fn test_subdivisions_capped() {
+        let displacement = Parser {}.get_displacement_from_json(&json!({"subdivisions": 40}));
+
+        assert_eq!(displacement.subdivisions, MAX_SUBDIVISIONS);
+        assert_eq!(
+            Parser {}
+                .get_displacement_from_json(&json!({}))
+                .subdivisions,
+            3
+        );
+    }
+}
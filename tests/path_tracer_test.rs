@@ -0,0 +1,108 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// path tracer tests
+//
+
+use raytracer::config::Config;
+use raytracer::renderer::parsing::Parser;
+use raytracer::renderer::Renderer;
+use serde_json::json;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A wall lit by nothing but the glowing mesh hanging in front of it
+    fn lit_wall(mesh: serde_json::Value) -> Renderer {
+        Parser {}.get_renderer_from_json(
+            &json!({
+                "camera": {
+                    "fov": 10,
+                    "integrator": "path",
+                    "recursivity": 1,
+                    "super_sampling": 1
+                },
+                "skybox": {
+                    "texture": {"color": {"r": 0, "g": 0, "b": 0}}
+                },
+                "primitives": [
+                    {
+                        "type": "plane",
+                        "normal": {"x": 0, "y": -1, "z": 0},
+                        "transform": {"pos": {"x": 0, "y": 6, "z": 0}},
+                        "texture": {
+                            "color": {"r": 255, "g": 255, "b": 255},
+                            "diffuse": 1.0,
+                            "specular": 0.0,
+                            "metalness": 0.0
+                        }
+                    },
+                    mesh
+                ]
+            }),
+            9,
+            9,
+        )
+    }
+
+    fn mean_radiance(renderer: &Renderer, samples: usize) -> f64 {
+        let config = Config::default();
+        let mut total = 0.0;
+
+        for _ in 0..samples {
+            let color = renderer.render_pixel(4, 4, &config);
+            total += color.x + color.y + color.z;
+        }
+        total / samples as f64
+    }
+
+    /// Checks that sampling the lights gives back what the bounces find alone
+    fn assert_lights_match_bounces(mesh: serde_json::Value, lights: usize) {
+        let with_lights = lit_wall(mesh.clone());
+        let mut without_lights = lit_wall(mesh);
+        without_lights.lights.lights.clear();
+
+        assert_eq!(with_lights.lights.lights.len(), lights);
+        let sampled = mean_radiance(&with_lights, 20000);
+        let found = mean_radiance(&without_lights, 20000);
+
+        assert!(found > 0.0);
+        assert!(
+            (sampled - found).abs() < 0.1 * found,
+            "{} != {}",
+            sampled,
+            found
+        );
+    }
+
+    #[test]
+    fn test_emissive_mesh_counted_once() {
+        assert_lights_match_bounces(
+            json!({
+                "type": "mesh",
+                "file": "tests/fixtures/cube.obj",
+                "transform": {"pos": {"x": 0, "y": 4, "z": 1.5}},
+                "texture": {
+                    "color": {"r": 0, "g": 0, "b": 0},
+                    "emission": {"r": 255, "g": 255, "b": 255},
+                    "emission_strength": 1.0
+                }
+            }),
+            1,
+        );
+    }
+
+    #[test]
+    fn test_emissive_materials_lights() {
+        assert_lights_match_bounces(
+            json!({
+                "type": "mesh",
+                "file": "tests/fixtures/lamps.obj",
+                "transform": {"pos": {"x": 0, "y": 4, "z": 0}}
+            }),
+            2,
+        );
+    }
+}
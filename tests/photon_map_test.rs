@@ -0,0 +1,84 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// photon map tests
+//
+
+use raytracer::renderer::photon_map::{Photon, PhotonMap};
+use raytracer::vector::Vector;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Photons on a slanted grid so that no two of them share a distance
+    fn grid() -> Vec<Photon> {
+        let mut photons = Vec::new();
+
+        for i in 0..20 {
+            for j in 0..20 {
+                photons.push(Photon {
+                    position: Vector {
+                        x: i as f64 * 0.1 + j as f64 * 0.013,
+                        y: j as f64 * 0.1,
+                        z: (i * j) as f64 * 0.001,
+                    },
+                    direction: Vector {
+                        x: 0.0,
+                        y: 0.0,
+                        z: -1.0,
+                    },
+                    power: Vector {
+                        x: 1.0,
+                        y: 1.0,
+                        z: 1.0,
+                    },
+                });
+            }
+        }
+        photons
+    }
+
+    #[test]
+    fn test_nearest_matches_brute_force() {
+        let photons = grid();
+        let map = PhotonMap::new(photons.clone());
+        let position = Vector {
+            x: 0.92,
+            y: 1.13,
+            z: 0.05,
+        };
+        let mut distances: Vec<f64> = photons
+            .iter()
+            .map(|photon| (photon.position - position).len2())
+            .collect();
+        distances.sort_by(|a, b| a.total_cmp(b));
+        let (found, radius2) = map.nearest(position, 10, 1.0);
+
+        assert!(!map.is_empty() && PhotonMap::new(Vec::new()).is_empty());
+        assert_eq!(map.nearest(position, 1000, f64::INFINITY).0.len(), 400);
+        assert_eq!(found.len(), 10);
+        for (photon, distance) in found.iter().zip(distances.iter()) {
+            assert_eq!((photon.position - position).len2(), *distance);
+        }
+        assert_eq!(radius2, distances[9]);
+    }
+
+    #[test]
+    fn test_nearest_stays_within_radius() {
+        let map = PhotonMap::new(grid());
+        let position = Vector {
+            x: 0.5,
+            y: 0.5,
+            z: 0.0,
+        };
+        let (found, radius2) = map.nearest(position, 100, 0.15);
+
+        assert!(!found.is_empty() && found.len() < 100);
+        assert!((radius2 - 0.15 * 0.15).abs() < 1e-12);
+        assert!(found
+            .iter()
+            .all(|photon| (photon.position - position).len() < 0.15));
+    }
+}
@@ -0,0 +1,60 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// scene inclusion tests
+//
+
+use raytracer::renderer::parsing::Parser;
+use serde_json::json;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(found: f64, expected: f64) {
+        assert!((found - expected).abs() < 1e-9, "{} != {}", found, expected);
+    }
+
+    #[test]
+    fn test_included_scene_materials() {
+        let renderer = Parser {}.get_renderer_from_json(
+            &json!({
+                "scenes": [{
+                    "file": "tests/fixtures/included.json",
+                    "transform": {"pos": {"x": 10, "y": 0, "z": 0}}
+                }],
+                "materials": {
+                    "gold": {
+                        "color": {"r": 255, "g": 200, "b": 60},
+                        "metalness": 0.8,
+                        "roughness": 0.1,
+                        "diffuse": 0.6
+                    }
+                }
+            }),
+            36,
+            64,
+        );
+
+        assert_eq!(renderer.primitives.len(), 2);
+        for primitive in renderer.primitives.iter() {
+            let transform = primitive.get_transform();
+            assert_close(transform.pos.x, 10.0);
+            assert_close(transform.pos.y, 5.0);
+        }
+        let plain = renderer.primitives[0].get_texture();
+        assert_eq!(plain.color.g, 200.0);
+        assert_close(plain.metalness, 0.8);
+        assert_close(plain.roughness, 0.1);
+        assert_close(plain.diffuse, 0.6);
+
+        // the scene's own material builds on the inherited one, and the
+        // object overrides it again
+        let polished = renderer.primitives[1].get_texture();
+        assert_eq!(polished.color.g, 200.0);
+        assert_close(polished.metalness, 0.8);
+        assert_close(polished.roughness, 0.05);
+        assert_close(polished.diffuse, 0.2);
+    }
+}
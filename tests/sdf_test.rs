@@ -0,0 +1,198 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// sdf tests
+//
+
+use raytracer::renderer::parsing::Parser;
+use raytracer::renderer::primitives::sdf::SdfNode;
+use raytracer::renderer::primitives::Object;
+use raytracer::vector::Vector;
+use serde_json::{json, Value};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vector(x: f64, y: f64, z: f64) -> Vector {
+        Vector { x, y, z }
+    }
+
+    fn node(json: Value) -> SdfNode {
+        Parser {}.get_sdf_node_from_json(&json).unwrap()
+    }
+
+    fn assert_close(found: f64, expected: f64) {
+        assert!((found - expected).abs() < 1e-9, "{} != {}", found, expected);
+    }
+
+    #[test]
+    fn test_node_defaults() {
+        match node(json!({"type": "box"})) {
+            SdfNode::Box { center, half_size } => {
+                assert_eq!(center, vector(0.0, 0.0, 0.0));
+                assert_eq!(half_size, vector(0.5, 0.5, 0.5));
+            }
+            other => panic!("{:?}", other),
+        }
+        match node(json!({"type": "torus", "center": {"x": 1.0, "y": 2.0, "z": 3.0}})) {
+            SdfNode::Torus {
+                center,
+                major_radius,
+                minor_radius,
+            } => {
+                assert_eq!(center, vector(1.0, 2.0, 3.0));
+                assert_eq!((major_radius, minor_radius), (1.0, 0.25));
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_nested_nodes() {
+        let shape = node(json!({
+            "type": "smooth_union",
+            "smoothness": 0.2,
+            "left": {"type": "sphere", "radius": 2.0},
+            "right": {"type": "twist", "child": {"type": "capsule"}}
+        }));
+
+        match shape {
+            SdfNode::SmoothUnion {
+                left,
+                right,
+                smoothness,
+            } => {
+                assert_eq!(smoothness, 0.2);
+                assert!(matches!(*left, SdfNode::Sphere { radius, .. } if radius == 2.0));
+                assert!(matches!(*right, SdfNode::Twist { strength, .. } if strength == 1.0));
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_invalid_nodes() {
+        let parser = Parser {};
+
+        assert!(parser.get_sdf_node_from_json(&json!({})).is_none());
+        assert!(parser
+            .get_sdf_node_from_json(&json!({"type": "teapot"}))
+            .is_none());
+        // a missing child fails the whole tree
+        assert!(parser
+            .get_sdf_node_from_json(&json!({"type": "smooth_union", "left": {"type": "sphere"}}))
+            .is_none());
+    }
+
+    #[test]
+    fn test_primitive_distances() {
+        let sphere = node(json!({"type": "sphere", "radius": 2.0}));
+        let cube = node(json!({"type": "box", "size": {"x": 2.0, "y": 2.0, "z": 2.0}}));
+        let round = node(
+            json!({"type": "round_box", "size": {"x": 2.0, "y": 2.0, "z": 2.0}, "radius": 0.5}),
+        );
+        let torus = node(json!({"type": "torus", "major_radius": 2.0, "minor_radius": 0.5}));
+        let capsule = node(json!({
+            "type": "capsule",
+            "start": {"x": 0.0, "y": 0.0, "z": -1.0},
+            "end": {"x": 0.0, "y": 0.0, "z": 1.0},
+            "radius": 0.5
+        }));
+
+        assert_close(sphere.distance(vector(3.0, 0.0, 0.0)), 1.0);
+        assert_close(sphere.distance(vector(0.0, 0.0, 0.0)), -2.0);
+        assert_close(cube.distance(vector(2.0, 0.0, 0.0)), 1.0);
+        assert_close(cube.distance(vector(2.0, 2.0, 0.0)), 2.0_f64.sqrt());
+        assert_close(cube.distance(vector(0.5, 0.0, 0.0)), -0.5);
+        assert_close(round.distance(vector(2.0, 0.0, 0.0)), 1.0);
+        assert_close(
+            round.distance(vector(2.0, 2.0, 0.0)),
+            2.0_f64.sqrt() * 1.5 - 0.5,
+        );
+        assert_close(torus.distance(vector(2.0, 0.0, 0.0)), -0.5);
+        assert_close(torus.distance(vector(0.0, 0.0, 0.0)), 1.5);
+        assert_close(capsule.distance(vector(1.0, 0.0, 0.0)), 0.5);
+        assert_close(capsule.distance(vector(0.0, 0.0, 3.0)), 1.5);
+    }
+
+    #[test]
+    fn test_operation_distances() {
+        let sharp = |kind: &str| {
+            node(json!({
+                "type": kind,
+                "smoothness": 0.0,
+                "left": {"type": "sphere", "radius": 1.0},
+                "right": {"type": "sphere", "center": {"x": 1.0, "y": 0.0, "z": 0.0}, "radius": 1.0}
+            }))
+        };
+        let point = vector(-1.5, 0.0, 0.0);
+
+        // without smoothness the operations are plain min and max
+        assert_close(sharp("smooth_union").distance(point), 0.5);
+        assert_close(sharp("smooth_intersect").distance(point), 1.5);
+        assert_close(
+            sharp("smooth_subtract").distance(vector(0.0, 0.0, 0.0)),
+            0.0,
+        );
+        assert_close(
+            sharp("smooth_subtract").distance(vector(-0.5, 0.0, 0.0)),
+            -0.5,
+        );
+        // blending pulls the union in between the shapes
+        let smooth = node(json!({
+            "type": "smooth_union",
+            "smoothness": 0.5,
+            "left": {"type": "sphere", "radius": 1.0},
+            "right": {"type": "sphere", "center": {"x": 3.0, "y": 0.0, "z": 0.0}, "radius": 1.0}
+        }));
+        assert!(smooth.distance(vector(1.5, 0.0, 0.0)) < 0.5);
+    }
+
+    #[test]
+    fn test_domain_distances() {
+        let repeat = node(json!({
+            "type": "repeat",
+            "period": {"x": 4.0, "y": 0.0, "z": 0.0},
+            "child": {"type": "sphere"}
+        }));
+        let twist = node(json!({
+            "type": "twist",
+            "strength": 1.0,
+            "child": {"type": "box", "size": {"x": 4.0, "y": 1.0, "z": 10.0}}
+        }));
+
+        assert_close(repeat.distance(vector(8.0, 0.0, 0.0)), -1.0);
+        assert_close(repeat.distance(vector(8.0, 3.0, 0.0)), 2.0);
+        // a quarter turn up the z axis brings the long side of the box around
+        let turned = std::f64::consts::FRAC_PI_2;
+        assert!(twist.distance(vector(0.0, 1.5, 0.0)) > 0.0);
+        assert!(twist.distance(vector(0.0, 1.5, turned)) < 0.0);
+    }
+
+    #[test]
+    fn test_intervals() {
+        let sdf = Parser {}.get_sdf_from_json(&json!({
+            "shape": {
+                "type": "smooth_subtract",
+                "smoothness": 0.0,
+                "left": {"type": "sphere", "radius": 2.0},
+                "right": {"type": "sphere", "radius": 1.0}
+            }
+        }));
+        let spans: Vec<(f64, f64)> = sdf
+            .unwrap()
+            .intervals(vector(2.0, 0.0, 0.0), vector(0.0, 0.0, 0.0))
+            .iter()
+            .map(|span| (span.enter.progress, span.exit.progress))
+            .collect();
+        let expected = [(-1.0, -0.5), (0.5, 1.0)];
+
+        assert_eq!(spans.len(), expected.len(), "{:?}", spans);
+        for ((enter, exit), (expected_enter, expected_exit)) in spans.iter().zip(expected) {
+            assert!((enter - expected_enter).abs() < 1e-3, "{:?}", spans);
+            assert!((exit - expected_exit).abs() < 1e-3, "{:?}", spans);
+        }
+    }
+}
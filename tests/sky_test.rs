@@ -0,0 +1,69 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// sky tests
+//
+
+use raytracer::renderer::parsing::Parser;
+use raytracer::renderer::sky::Sky;
+use raytracer::vector::Vector;
+use serde_json::json;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(found: Vector, expected: Vector) {
+        assert!(
+            (found - expected).len() < 1e-9,
+            "{:?} != {:?}",
+            found,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_sun_below_horizon() {
+        let sky = Sky::new(-10.0, 30.0, 3.0, 1.0);
+
+        assert!(sky.sun.z < 0.0);
+        assert_eq!(sky.sun_color().len(), 0.0);
+    }
+
+    #[test]
+    fn test_sun_disk() {
+        let sky = Sky::new(30.0, 60.0, 3.0, 1.0);
+        let beside = (sky.sun
+            + Vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.1,
+            })
+        .normalize();
+
+        assert!(sky.sun_color().len() > 0.0);
+        assert_close(
+            sky.radiance(sky.sun) - sky.scattered(sky.sun),
+            sky.sun_color() * 40.0,
+        );
+        assert_close(sky.radiance(beside), sky.scattered(beside));
+    }
+
+    #[test]
+    fn test_sky_defaults() {
+        let sky = Parser {}.get_sky_from_json(&json!({}));
+        let angle = 45.0_f64.to_radians();
+
+        assert_close(
+            sky.sun,
+            Vector {
+                x: 0.0,
+                y: angle.cos(),
+                z: angle.sin(),
+            },
+        );
+        assert_eq!(sky.turbidity, 3.0);
+        assert_eq!(sky.intensity, 1.0);
+    }
+}
@@ -0,0 +1,89 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// texture tests
+//
+
+use raytracer::renderer::types::{Image, ImageFilter, ImageWrap, Texture, TexturesTypes};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2x2 image, red and green on the top row, blue and white on the bottom one
+    fn image_texture(name: &str, filter: ImageFilter, wrap: ImageWrap) -> Texture {
+        let file = std::env::temp_dir().join(format!("rustracer_texture_test_{}.ppm", name));
+        let mut data = b"P6\n2 2\n255\n".to_vec();
+        data.extend_from_slice(&[255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 255]);
+        std::fs::write(&file, data).unwrap();
+
+        let mut texture = Texture {
+            texture_type: TexturesTypes::Image as u64,
+            image: Image::from_file(&file.to_string_lossy()),
+            ..Texture::default()
+        };
+        texture.sampler.filter = filter;
+        texture.sampler.wrap = wrap;
+        texture
+    }
+
+    #[test]
+    fn test_nearest_orientation() {
+        let texture = image_texture("nearest", ImageFilter::Nearest, ImageWrap::Repeat);
+        // u runs from the right edge of the image and v from its bottom
+        let top_left = texture.texture(0.75, 0.75);
+        let top_right = texture.texture(0.25, 0.75);
+        let bottom_right = texture.texture(0.25, 0.25);
+
+        assert_eq!((top_left.r, top_left.g, top_left.b), (255.0, 0.0, 0.0));
+        assert_eq!((top_right.r, top_right.g, top_right.b), (0.0, 255.0, 0.0));
+        assert_eq!(
+            (bottom_right.r, bottom_right.g, bottom_right.b),
+            (255.0, 255.0, 255.0)
+        );
+    }
+
+    #[test]
+    fn test_wrap_modes() {
+        let repeat = image_texture("repeat", ImageFilter::Nearest, ImageWrap::Repeat);
+        let clamp = image_texture("clamp", ImageFilter::Nearest, ImageWrap::Clamp);
+        let mirror = image_texture("mirror", ImageFilter::Nearest, ImageWrap::Mirror);
+
+        assert_eq!(repeat.texture(-0.25, 0.75).g, 0.0);
+        assert_eq!(clamp.texture(-0.25, 0.75).g, 255.0);
+        assert_eq!(mirror.texture(-0.25, 0.75).g, 255.0);
+        assert_eq!(mirror.texture(-0.75, 0.75).r, 255.0);
+    }
+
+    #[test]
+    fn test_bilinear_blends_texels() {
+        let texture = image_texture("bilinear", ImageFilter::Bilinear, ImageWrap::Clamp);
+        let center = texture.texture(0.5, 0.5);
+
+        assert!((center.r - 127.5).abs() < 1e-9);
+        assert!((center.g - 127.5).abs() < 1e-9);
+        assert!((center.b - 127.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_trilinear_averages_far_away() {
+        let texture = image_texture("trilinear", ImageFilter::Trilinear, ImageWrap::Repeat);
+        let far = texture.sample(0.25, 0.75, 10.0);
+
+        assert_eq!((far.r, far.g, far.b), (127.0, 127.0, 127.0));
+    }
+
+    #[test]
+    fn test_image_from_bytes() {
+        let texture = Texture {
+            texture_type: TexturesTypes::Image as u64,
+            image: Image::from_bytes("texture_test_bytes", b"P6\n1 1\n255\n\x10\x20\x30"),
+            ..Texture::default()
+        };
+        let color = texture.texture(0.5, 0.5);
+
+        assert_eq!((texture.image.width, texture.image.height), (1, 1));
+        assert_eq!((color.r, color.g, color.b), (16.0, 32.0, 48.0));
+    }
+}
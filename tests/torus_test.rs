@@ -0,0 +1,96 @@
+//
+// EPITECH PROJECT, 2023
+// Rustracer
+// File description:
+// torus tests
+//
+
+use raytracer::renderer::parsing::Parser;
+use raytracer::renderer::primitives::torus::Torus;
+use raytracer::renderer::primitives::Object;
+use raytracer::vector::Vector;
+use serde_json::json;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn torus() -> Box<Torus> {
+        Parser {}.get_torus_from_json(&json!({
+            "major_radius": 2.0,
+            "minor_radius": 0.5
+        }))
+    }
+
+    fn spans(origin: Vector, ray: Vector) -> Vec<(f64, f64)> {
+        torus()
+            .intervals(ray, origin)
+            .iter()
+            .map(|span| (span.enter.progress, span.exit.progress))
+            .collect()
+    }
+
+    fn assert_spans(found: Vec<(f64, f64)>, expected: &[(f64, f64)]) {
+        assert_eq!(found.len(), expected.len(), "{:?}", found);
+        for ((enter, exit), (expected_enter, expected_exit)) in found.iter().zip(expected) {
+            assert!((enter - expected_enter).abs() < 1e-6, "{:?}", found);
+            assert!((exit - expected_exit).abs() < 1e-6, "{:?}", found);
+        }
+    }
+
+    #[test]
+    fn test_intervals_through_both_sides() {
+        let found = spans(
+            Vector {
+                x: -5.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            Vector {
+                x: 2.0,
+                y: 0.0,
+                z: 0.0,
+            },
+        );
+
+        assert_spans(found, &[(1.25, 1.75), (3.25, 3.75)]);
+    }
+
+    #[test]
+    fn test_intervals_grazing_ray() {
+        // tangent to the top of the tube on both sides
+        let found = spans(
+            Vector {
+                x: -5.0,
+                y: 0.0,
+                z: 0.5,
+            },
+            Vector {
+                x: 1.0,
+                y: 0.0,
+                z: 0.0,
+            },
+        );
+
+        assert!(found.is_empty(), "{:?}", found);
+    }
+
+    #[test]
+    fn test_intervals_touching_inner_edge() {
+        // the double root where the ray touches the hole stays inside the span
+        let found = spans(
+            Vector {
+                x: -5.0,
+                y: 1.5,
+                z: 0.0,
+            },
+            Vector {
+                x: 1.0,
+                y: 0.0,
+                z: 0.0,
+            },
+        );
+
+        assert_spans(found, &[(3.0, 7.0)]);
+    }
+}
@@ -7,6 +7,7 @@
 
 use raytracer::vector;
 use vector::resolve_quadratic_equation;
+use vector::resolve_quartic_equation;
 use vector::Vector;
 
 #[cfg(test)]
@@ -125,6 +126,26 @@ mod tests {
         assert_eq!(result, vec![2.0, 1.0]);
     }
 
+    #[test]
+    fn test_resolve_quartic_equation() {
+        // Test case 1: No roots
+        let result = resolve_quartic_equation(1.0, 0.0, 0.0, 0.0, 1.0);
+        assert!(result.is_empty());
+
+        // Test case 2: Biquadratic, (x^2 - 1)(x^2 - 4)
+        let mut result = resolve_quartic_equation(1.0, 0.0, -5.0, 0.0, 4.0);
+        result.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(result, vec![-2.0, -1.0, 1.0, 2.0]);
+
+        // Test case 3: Four roots, (x - 1)(x - 2)(x - 3)(x - 4)
+        let mut result = resolve_quartic_equation(2.0, -20.0, 70.0, -100.0, 48.0);
+        result.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(result.len(), 4);
+        for (root, expected) in result.iter().zip([1.0, 2.0, 3.0, 4.0]) {
+            assert!((root - expected).abs() < 1e-9);
+        }
+    }
+
     #[test]
     fn test_reflect_vector() {
         let vec = Vector {